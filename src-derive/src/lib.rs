@@ -3,63 +3,68 @@ use proc_macro::TokenStream;
 use quote::quote;
 use syn;
 
-#[derive(deluxe::ExtractAttributes)]
-#[deluxe(attributes(conversion))]
-struct DistanceSIUnit {
-    meter: f32,
+/// Implements [`Unit`] and the arithmetic with floats shared by every kind of unit for the
+/// struct `ident`, along with `conversion`, the impl of the trait giving its SI factor.
+fn implement_unit(
+    ident: &syn::Ident,
     integer: Option<bool>,
-}
-
-fn implement_distance_unit(
-    input: proc_macro2::TokenStream,
-) -> deluxe::Result<proc_macro2::TokenStream> {
-    let mut ast: syn::DeriveInput = syn::parse2(input)?;
-
-    let DistanceSIUnit { meter, integer } = deluxe::extract_attributes(&mut ast)?;
-
-    let ident = &ast.ident;
-
+    conversion: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
     let discreetize = match integer {
         None | Some(false) => quote! { value },
         Some(true) => quote! {value.round() as usize},
     };
 
-    let cast_int_to_f32 = match integer {
+    let cast_int_to_f64 = match integer {
         None | Some(false) => quote! {},
-        Some(true) => quote! {as f32},
+        Some(true) => quote! {as f64},
     };
 
-    Ok(quote! {
+    quote! {
         impl Unit for #ident {
-            fn new(value: f32) -> Self {
+            fn new(value: f64) -> Self {
                 Self {value: #discreetize}
             }
         }
 
-        impl DistanceUnit for #ident {
-            const METER: f32 = #meter;
+        #conversion
+
+        impl Div<#ident> for f64 {
+            type Output = f64;
+
+            fn div(self, rhs: #ident) -> Self::Output {
+                self / rhs.value #cast_int_to_f64
+            }
         }
 
         impl Div<#ident> for f32 {
             type Output = f32;
 
             fn div(self, rhs: #ident) -> Self::Output {
-                self / rhs.value #cast_int_to_f32
+                (self as f64 / rhs) as f32
             }
         }
 
-        impl Div<f32> for #ident {
+        impl Div<f64> for #ident {
             type Output = Self;
 
-            fn div(self, rhs: f32) -> Self::Output {
-                Self::new(self.value #cast_int_to_f32 / rhs)
+            fn div(self, rhs: f64) -> Self::Output {
+                Self::new(self.value #cast_int_to_f64 / rhs)
             }
         }
-        impl Mul<f32> for #ident {
+        impl Mul<f64> for #ident {
             type Output = Self;
 
-            fn mul(self, rhs: f32) -> Self::Output {
-                Self::new(self.value #cast_int_to_f32 * rhs)
+            fn mul(self, rhs: f64) -> Self::Output {
+                Self::new(self.value #cast_int_to_f64 * rhs)
+            }
+        }
+
+        impl Mul<#ident> for f64 {
+            type Output = f64;
+
+            fn mul(self, rhs: #ident) -> Self::Output {
+                self * rhs.value #cast_int_to_f64
             }
         }
 
@@ -67,10 +72,37 @@ fn implement_distance_unit(
             type Output = f32;
 
             fn mul(self, rhs: #ident) -> Self::Output {
-                self * rhs.value #cast_int_to_f32
+                (self as f64 * rhs) as f32
             }
         }
-    })
+    }
+}
+
+#[derive(deluxe::ExtractAttributes)]
+#[deluxe(attributes(conversion))]
+struct DistanceSIUnit {
+    meter: f64,
+    integer: Option<bool>,
+}
+
+fn implement_distance_unit(
+    input: proc_macro2::TokenStream,
+) -> deluxe::Result<proc_macro2::TokenStream> {
+    let mut ast: syn::DeriveInput = syn::parse2(input)?;
+
+    let DistanceSIUnit { meter, integer } = deluxe::extract_attributes(&mut ast)?;
+
+    let ident = &ast.ident;
+
+    Ok(implement_unit(
+        ident,
+        integer,
+        quote! {
+            impl DistanceUnit for #ident {
+                const METER: f64 = #meter;
+            }
+        },
+    ))
 }
 
 #[proc_macro_derive(LengthQuantity, attributes(conversion))]
@@ -81,7 +113,7 @@ pub fn derive_unit_length(input: TokenStream) -> TokenStream {
 #[derive(deluxe::ExtractAttributes)]
 #[deluxe(attributes(conversion))]
 struct TimeSIUnit {
-    second: f32,
+    second: f64,
     integer: Option<bool>,
 }
 
@@ -94,61 +126,85 @@ fn implement_time_unit(
 
     let ident = &ast.ident;
 
-    let discreetize = match integer {
-        None | Some(false) => quote! { value },
-        Some(_) => quote! {value.round() as usize},
-    };
+    Ok(implement_unit(
+        ident,
+        integer,
+        quote! {
+            impl TemporalUnit for #ident {
+                const SECOND: f64 = #second;
+            }
+        },
+    ))
+}
 
-    let cast_int_to_f32 = match integer {
-        None | Some(false) => quote! {},
-        Some(true) => quote! {as f32},
-    };
+#[proc_macro_derive(TimeQuantity, attributes(conversion))]
+pub fn derive_unit_time(input: TokenStream) -> TokenStream {
+    implement_time_unit(input.into()).unwrap().into()
+}
 
-    Ok(quote! {
-        impl Unit for #ident {
-            fn new(value: f32) -> Self {
-                Self {value: #discreetize}
-            }
-        }
+#[derive(deluxe::ExtractAttributes)]
+#[deluxe(attributes(conversion))]
+struct MassSIUnit {
+    kilogram: f64,
+    integer: Option<bool>,
+}
 
-        impl TemporalUnit for #ident {
-            const SECOND: f32 = #second;
-        }
+fn implement_mass_unit(
+    input: proc_macro2::TokenStream,
+) -> deluxe::Result<proc_macro2::TokenStream> {
+    let mut ast: syn::DeriveInput = syn::parse2(input)?;
 
-        impl Div<#ident> for f32 {
-            type Output = f32;
+    let MassSIUnit { kilogram, integer } = deluxe::extract_attributes(&mut ast)?;
 
-            fn div(self, rhs: #ident) -> Self::Output {
-                self / rhs.value #cast_int_to_f32
+    let ident = &ast.ident;
+
+    Ok(implement_unit(
+        ident,
+        integer,
+        quote! {
+            impl MassUnit for #ident {
+                const KILOGRAM: f64 = #kilogram;
             }
-        }
+        },
+    ))
+}
 
-        impl Div<f32> for #ident {
-            type Output = Self;
+#[proc_macro_derive(MassQuantity, attributes(conversion))]
+pub fn derive_unit_mass(input: TokenStream) -> TokenStream {
+    implement_mass_unit(input.into()).unwrap().into()
+}
 
-            fn div(self, rhs: f32) -> Self::Output {
-                Self::new(self.value #cast_int_to_f32 / rhs)
-            }
-        }
-        impl Mul<f32> for #ident {
-            type Output = Self;
+#[derive(deluxe::ExtractAttributes)]
+#[deluxe(attributes(conversion))]
+struct VelocitySIUnit {
+    meter_per_second: f64,
+    integer: Option<bool>,
+}
 
-            fn mul(self, rhs: f32) -> Self::Output {
-                Self::new(self.value #cast_int_to_f32 * rhs)
-            }
-        }
+fn implement_velocity_unit(
+    input: proc_macro2::TokenStream,
+) -> deluxe::Result<proc_macro2::TokenStream> {
+    let mut ast: syn::DeriveInput = syn::parse2(input)?;
 
-        impl Mul<#ident> for f32 {
-            type Output = f32;
+    let VelocitySIUnit {
+        meter_per_second,
+        integer,
+    } = deluxe::extract_attributes(&mut ast)?;
 
-            fn mul(self, rhs: #ident) -> Self::Output {
-                self * rhs.value #cast_int_to_f32
+    let ident = &ast.ident;
+
+    Ok(implement_unit(
+        ident,
+        integer,
+        quote! {
+            impl VelocityUnit for #ident {
+                const METER_PER_SECOND: f64 = #meter_per_second;
             }
-        }
-    })
+        },
+    ))
 }
 
-#[proc_macro_derive(TimeQuantity, attributes(conversion))]
-pub fn derive_unit_time(input: TokenStream) -> TokenStream {
-    implement_time_unit(input.into()).unwrap().into()
+#[proc_macro_derive(VelocityQuantity, attributes(conversion))]
+pub fn derive_unit_velocity(input: TokenStream) -> TokenStream {
+    implement_velocity_unit(input.into()).unwrap().into()
 }
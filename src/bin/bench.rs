@@ -0,0 +1,90 @@
+//! Times one force evaluation on a single thread and on every core, for growing clusters.
+//!
+//! Run with `cargo run --release --bin bench`.
+use anisa_rs::bodies::{AstroBody, BodySet};
+use anisa_rs::gravity::{Gravity, Solver};
+use anisa_rs::quantities::spatial::{Cartesian, Velocity};
+use anisa_rs::units::length::meter::Meter;
+use clap::Parser;
+use nalgebra::SVector;
+use rayon::ThreadPoolBuilder;
+use std::time::{Duration, Instant};
+
+#[derive(clap::Parser)]
+struct Args {
+    /// Numbers of bodies to time
+    #[arg(short, default_values_t = [1_000, 5_000, 20_000])]
+    n: Vec<usize>,
+    /// Threads to compare against a single one, one per core by default
+    #[arg(long)]
+    threads: Option<usize>,
+    /// Evaluations to average over
+    #[arg(long, default_value_t = 1)]
+    repeat: u32,
+}
+
+fn main() {
+    let args = Args::parse();
+    let threads = args.threads.unwrap_or_else(rayon::current_num_threads);
+    let single = ThreadPoolBuilder::new().num_threads(1).build().unwrap();
+    let parallel = ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .build()
+        .unwrap();
+
+    println!("solver       n  1 thread  {threads} threads  speedup");
+    for n in args.n {
+        let bodies = cluster(n, 42);
+        for (name, solver) in [
+            ("direct", Solver::Direct),
+            ("barnes-hut", Solver::BarnesHut { opening_angle: 0.5 }),
+        ] {
+            let gravity = Gravity::new(1.).with_solver(solver).with_softening(1e-3);
+            let time = |pool: &rayon::ThreadPool| {
+                let mut bodies = bodies.clone();
+                let start = Instant::now();
+                for _ in 0..args.repeat {
+                    pool.install(|| gravity.gravitate_all(&mut bodies));
+                }
+                (start.elapsed() / args.repeat, bodies)
+            };
+            let (one, expected) = time(&single);
+            let (many, bodies) = time(&parallel);
+            assert!(
+                bodies.acceleration == expected.acceleration,
+                "forces differ between 1 and {threads} threads"
+            );
+            println!(
+                "{name:<10} {n:>5} {:>9} {:>10}  {:>6.2}x",
+                millis(one),
+                millis(many),
+                one.as_secs_f64() / many.as_secs_f64()
+            );
+        }
+    }
+}
+
+fn millis(duration: Duration) -> String {
+    format!("{:.1} ms", duration.as_secs_f64() * 1e3)
+}
+
+/// `n` bodies of random mass spread uniformly in a unit cube, from a fixed `seed`.
+fn cluster(n: usize, seed: u64) -> BodySet<f64, 3> {
+    // xorshift64, so the cluster is the same on every run
+    let mut state = seed.max(1);
+    let mut random = move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        (state >> 11) as f64 / (1u64 << 53) as f64
+    };
+    (0..n)
+        .map(|_| {
+            AstroBody::<Cartesian<f64, 3, Meter>, 3, 1>::new_dynamic(
+                0.5 + random(),
+                Cartesian::from_vector(SVector::from_fn(|_, _| random())),
+                Velocity::zero(),
+            )
+        })
+        .collect()
+}
@@ -1,12 +1,15 @@
+use crate::config::Planet;
 use crate::quantities::dynamics::Force;
-use crate::quantities::spatial::{Cartesian, Quantity, Velocity};
-use crate::quantities::Tensor;
-use nalgebra::{DMatrix, DVector};
-use std::collections::VecDeque;
-use crate::Planet;
-use crate::units::length::pixel::Pixel;
-use crate::units::length::DistanceUnit;
+use crate::quantities::spatial::{Acceleration, Cartesian, Quantity, Velocity};
+use crate::quantities::{Float, Tensor};
 use crate::units::length::meter::Meter;
+use nalgebra::{DMatrix, DVector, SVector};
+
+pub mod set;
+pub mod tracer;
+
+pub use set::{BodySet, BodyView, Snapshot};
+pub use tracer::{Tracer, Trail};
 
 #[derive(Default, Debug, Clone)]
 pub struct AstroBody<X, const R: usize, const C: usize>
@@ -16,40 +19,90 @@ where
     pub pos: X,
     pub velocity: Velocity<X, R, C>,
     pub force: Force<X, R, C>,
-    pub mass: f32,
+    pub mass: X::Value,
     pub radius: f32,
-    pub color: Option<String>
+    /// Physical radius in meters, used to detect collisions. Zero for a point mass.
+    pub physical_radius: X::Value,
+    pub kind: Kind,
+    pub color: Option<String>,
 }
 
-impl From<&Planet> for AstroBody<Cartesian<f32, 2, Meter>, 2, 1> where {
+/// Whether a body is a source of gravity, selected from the config with e.g. `kind = "particle"`.
+#[derive(serde::Deserialize, Default, Debug, Copy, Clone, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Kind {
+    /// Attracts and is attracted by every other massive body.
+    #[default]
+    Massive,
+    /// Massless test particle, attracted by massive bodies only. Its `force` is the force per
+    /// unit mass, i.e. its acceleration.
+    Particle,
+}
+
+impl<F: Float, const D: usize> From<&Planet> for AstroBody<Cartesian<F, D, Meter>, D, 1> {
     fn from(value: &Planet) -> Self {
-        AstroBody::new_static(value.mass, Cartesian::zero()).set_color(value.color.clone())
+        AstroBody::new_static(F::cast_from(value.mass.value()), Cartesian::zero())
+            .set_kind(value.kind)
+            .set_color(value.color.clone())
+            .set_physical_radius(F::cast_from(value.radius.value()))
     }
 }
 
-impl AstroBody<Cartesian<f32, 2, Meter>, 2, 1> {
-
+impl<F: Float, const D: usize> AstroBody<Cartesian<F, D, Meter>, D, 1> {
     pub fn set_color(mut self, color: String) -> Self {
         self.color = Some(color);
         self
     }
-    pub fn new_static(mass: f32, pos: Cartesian<f32, 2, Pixel>) -> Self {
+    pub fn set_physical_radius(mut self, physical_radius: F) -> Self {
+        self.physical_radius = physical_radius;
+        self
+    }
+    /// Makes the body a test particle or a massive one. Test particles have no mass.
+    pub fn set_kind(mut self, kind: Kind) -> Self {
+        self.kind = kind;
+        if kind == Kind::Particle {
+            self.mass = F::zero();
+            self.radius = 1.;
+        }
+        self
+    }
+    pub fn new_particle(
+        pos: Cartesian<F, D, Meter>,
+        velocity: Velocity<Cartesian<F, D, Meter>, D, 1>,
+    ) -> Self {
+        AstroBody::new_dynamic(F::zero(), pos, velocity).set_kind(Kind::Particle)
+    }
+    pub fn is_massive(&self) -> bool {
+        self.kind == Kind::Massive
+    }
+    /// Mass the force on the body is proportional to, 1 for test particles.
+    pub(crate) fn inertia(&self) -> F {
+        match self.kind {
+            Kind::Massive => self.mass,
+            Kind::Particle => F::one(),
+        }
+    }
+    /// Acceleration due to the currently stored force.
+    pub fn acceleration(&self) -> Acceleration<Cartesian<F, D, Meter>, D, 1> {
+        self.force.acceleration_of(&self.inertia())
+    }
+    pub fn new_static(mass: F, pos: Cartesian<F, D, Meter>) -> Self {
         AstroBody {
-            pos: Cartesian::from(pos),
+            pos,
             mass,
-            radius: mass.log10(),
+            radius: mass.as_f32().log10(),
             ..Default::default()
         }
     }
     pub fn new_dynamic(
-        mass: f32,
-        pos: Cartesian<f32, 2, Meter>,
-        velocity: Velocity<Cartesian<f32, 2, Meter>, 2, 1>,
+        mass: F,
+        pos: Cartesian<F, D, Meter>,
+        velocity: Velocity<Cartesian<F, D, Meter>, D, 1>,
     ) -> Self {
         AstroBody {
             pos,
             mass,
-            radius: mass * 0.5,
+            radius: mass.as_f32() * 0.5,
             velocity,
             ..Default::default()
         }
@@ -59,85 +112,77 @@ impl AstroBody<Cartesian<f32, 2, Meter>, 2, 1> {
     ///
     /// The function evaluates the Matrix:
     ///
-    ///     f32 = G * M \[ΔR\] \[M_others\]
+    /// ```text
+    /// f32 = G * M [ΔR] [M_others]
+    /// ```
     ///
     /// where:
     /// - G         = `grav`: the gravitational constant
     /// - M         = `self.mass`: mass of self
     /// - ΔR        = unit displacement, as dim X n matrix:
-    ///     ```matrix
+    ///     ```text
     ///       Δr        |` Δx_1 ... Δx_n  `|
-    ///     ------- =   |                  |
-    ///     | r |^3     |_ Δy_1 ... Δy_n  _|
+    ///     ------- =   |  ...         ... |
+    ///     | r |^3     |_ Δz_1 ... Δz_n  _|
     ///     ```
     /// - M_others  = mass of others, as n x 1 column vector:
-    ///     ```matrix
+    ///     ```text
     ///          |` m_1 `|
     ///     M =  |  ...  |
     ///          |_ m_n _|
     ///     ```
     ///
     /// It is safe to include reference to self in others as self-interactions are nullified.
+    ///
+    /// Simulations evaluate forces with
+    /// [`Gravity::gravitate_all`](crate::gravity::Gravity::gravitate_all) instead; this is kept
+    /// as a plain reference to check it against.
     pub fn gravitate(
         &mut self,
-        others: &Vec<AstroBody<Cartesian<f32, 2, Meter>, 2, 1>>,
-        grav: &f32,
-    ) -> Force<Cartesian<f32, 2, Meter>, 2, 1> {
+        others: &[AstroBody<Cartesian<F, D, Meter>, D, 1>],
+        grav: &F,
+    ) -> Force<Cartesian<F, D, Meter>, D, 1> {
         //      |` m_1 `|
         // M =  |  ...  |
         //      |_ m_n _|
-        let masses = DVector::<f32>::from_iterator(others.len(), others.iter().map(|x| x.mass));
-        let coords = others.iter().fold(Vec::<f32>::new(), |mut a, b| {
-            let data = (b.pos - self.pos).normed_values();
-            for mut i in data {
+        let masses = DVector::<F>::from_iterator(others.len(), others.iter().map(|x| x.mass));
+        let coords = others.iter().fold(Vec::<F>::new(), |mut a, b| {
+            let displacement = b.pos - self.pos;
+            let distance = displacement.covariant().norm();
+            for mut i in displacement.values() {
+                i /= distance.powi(3);
                 // If displacement is zero (interacts with self), i is Nan so we set it to 0.
-                if i.is_nan() {
-                    i = 0.
+                if !i.is_finite() {
+                    i = F::zero()
                 }
                 a.push(i)
             }
             a
         });
         //   Δr        |` Δx_1 ... Δx_n  `|
-        // ------- =   |                  |
-        // | r |^3     |_ Δy_1 ... Δy_n  _|
-        let matrix = DMatrix::<f32>::from_vec(2, others.len(), coords);
-        let f = (&matrix * masses).scale(*grav * self.mass);
-        self.force = Force::new(f[0], f[1]);
+        // ------- =   |  ...         ... |
+        // | r |^3     |_ Δz_1 ... Δz_n  _|
+        let matrix = DMatrix::<F>::from_vec(D, others.len(), coords);
+        let f = (&matrix * masses).scale(*grav * self.inertia());
+        self.force = Force::from_vector(SVector::from_column_slice(f.as_slice()));
         self.force
     }
-    pub fn update(&mut self, step_time: f32) -> &Self {
-        self.velocity +=
-            Velocity::from_acceleration(&self.force.acceleration_of(&self.mass), &step_time);
-        self.pos += Cartesian::from_velocity(&self.velocity, step_time);
-        self.force = Force::zero();
-        self
-    }
-    pub fn get_shift_from_origin(&self) -> Cartesian<f32, 2, Meter> {
-        Cartesian::zero() - self.pos
-    }
-    pub fn get_velocity_shift_from_origin(&self) -> Velocity<Cartesian<f32, 2, Meter>, 2, 1> {
-        Velocity::<Cartesian<f32, 2, Meter>, 2, 1>::zero() - self.velocity
-    }
-    pub fn shift_by(&mut self, &pos: &Cartesian<f32, 2, Meter>, &vel: &Velocity<Cartesian<f32, 2, Meter>, 2, 1>) {
-        self.pos += pos;
-        self.velocity += vel;
-    }
 }
 
 #[cfg(test)]
 mod test {
-    use crate::*;
-    use nalgebra::SimdComplexField;
+    use super::*;
     use crate::units::length::meter::Meter;
+    use nalgebra::SimdComplexField;
 
     /// Ensures that the gravity calculation is correct:
     /// - F_12 = -F21
     /// - F_1\[2+3\] + F_2\[1+3\] + F_3\[1+2\] = 0
     #[test]
     fn test_gravity() {
-        let mut host = AstroBody::new_static(10., Cartesian::new(-1., -1.));
-        let mut planet = AstroBody::new_static(0.4, Cartesian::new(1., 1.));
+        let mut host: AstroBody<Cartesian<f32, 2, Meter>, 2, 1> =
+            AstroBody::new_static(10., Cartesian::new(-1.496e9, -1.496e9));
+        let mut planet = AstroBody::new_static(0.4, Cartesian::new(1.496e9, 1.496e9));
 
         let mut lists = vec![host.clone(), planet.clone()];
 
@@ -146,7 +191,7 @@ mod test {
             -planet.gravitate(&lists, &0.1)
         );
 
-        let mut planet2 = AstroBody::new_static(0.4, Cartesian::new(0., 5.));
+        let mut planet2 = AstroBody::new_static(0.4, Cartesian::new(0., 7.48e9));
         lists.push(planet2.clone());
 
         assert_eq!(
@@ -162,22 +207,3 @@ mod test {
         )
     }
 }
-
-#[derive(Debug)]
-pub struct Tracer<X, const R: usize, const C: usize>
-where
-    X: Tensor<R, C>,
-{
-    pub pos: VecDeque<X>,
-}
-
-impl<X> Default for Tracer<X, 2, 1>
-where
-    X: Tensor<2, 1>,
-{
-    fn default() -> Self {
-        Tracer {
-            pos: VecDeque::with_capacity(10),
-        }
-    }
-}
@@ -0,0 +1,341 @@
+use super::{AstroBody, Kind};
+use crate::quantities::dynamics::Force;
+use crate::quantities::spatial::{Acceleration, Cartesian, Velocity};
+use crate::quantities::{Float, Tensor};
+use crate::units::length::meter::Meter;
+use nalgebra::SVector;
+
+/// Every body of a simulation, stored as one contiguous buffer per quantity (struct of arrays).
+///
+/// This is the layout the force solvers and integrators work on: a step reads and writes the
+/// buffers in place, without allocating (but for the tree of [`Solver::BarnesHut`], rebuilt at
+/// every force evaluation). Every buffer holds one entry per body, in the same order, and
+/// `acceleration` holds the result of the last force evaluation (see
+/// [`Gravity::gravitate_all`](crate::gravity::Gravity::gravitate_all)).
+///
+/// [`BodySet::get`] and [`BodySet::iter`] give a typed view of single bodies, and
+/// [`AstroBody`]s go in and out with [`BodySet::push`], [`BodySet::set`] and
+/// [`BodyView::to_body`].
+///
+/// [`Solver::BarnesHut`]: crate::gravity::Solver::BarnesHut
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct BodySet<F, const D: usize> {
+    pub pos: Vec<SVector<F, D>>,
+    pub velocity: Vec<SVector<F, D>>,
+    pub acceleration: Vec<SVector<F, D>>,
+    pub mass: Vec<F>,
+    /// Physical radius in meters, used to detect collisions.
+    pub physical_radius: Vec<F>,
+    /// Changed through [`BodySet::set`] and [`BodySet::retain`], which keep `massive` in step.
+    pub kind: Vec<Kind>,
+    /// Index of every massive body in order, the sources of gravity.
+    pub(crate) massive: Vec<usize>,
+    /// Size the viewer draws the body at, in pixels.
+    pub radius: Vec<f32>,
+    pub color: Vec<Option<String>>,
+}
+
+/// Positions and velocities of a [`BodySet`] at some point, e.g. the start of a step.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct Snapshot<F, const D: usize> {
+    pub pos: Vec<SVector<F, D>>,
+    pub velocity: Vec<SVector<F, D>>,
+}
+
+impl<F: Float, const D: usize> BodySet<F, D> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.pos.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pos.is_empty()
+    }
+
+    pub fn push(&mut self, body: AstroBody<Cartesian<F, D, Meter>, D, 1>) {
+        if body.kind == Kind::Massive {
+            self.massive.push(self.len());
+        }
+        self.pos.push(*body.pos.covariant());
+        self.velocity.push(*body.velocity.covariant());
+        self.acceleration.push(*body.acceleration().covariant());
+        self.mass.push(body.mass);
+        self.physical_radius.push(body.physical_radius);
+        self.kind.push(body.kind);
+        self.radius.push(body.radius);
+        self.color.push(body.color);
+    }
+
+    /// Replaces the body at `index`.
+    pub fn set(&mut self, index: usize, body: AstroBody<Cartesian<F, D, Meter>, D, 1>) {
+        self.pos[index] = *body.pos.covariant();
+        self.velocity[index] = *body.velocity.covariant();
+        self.acceleration[index] = *body.acceleration().covariant();
+        self.mass[index] = body.mass;
+        self.physical_radius[index] = body.physical_radius;
+        match (self.massive.binary_search(&index), body.kind) {
+            (Err(at), Kind::Massive) => self.massive.insert(at, index),
+            (Ok(at), Kind::Particle) => {
+                self.massive.remove(at);
+            }
+            _ => {}
+        }
+        self.kind[index] = body.kind;
+        self.radius[index] = body.radius;
+        self.color[index] = body.color;
+    }
+
+    /// Typed view of the body at `index`.
+    ///
+    /// # Panics
+    ///
+    /// If `index` is out of bounds.
+    pub fn get(&self, index: usize) -> BodyView<'_, F, D> {
+        assert!(
+            index < self.len(),
+            "no body {index} in a set of {}",
+            self.len()
+        );
+        BodyView { set: self, index }
+    }
+
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = BodyView<'_, F, D>> {
+        (0..self.len()).map(|index| BodyView { set: self, index })
+    }
+
+    /// Copies every body out into an [`AstroBody`].
+    pub fn to_vec(&self) -> Vec<AstroBody<Cartesian<F, D, Meter>, D, 1>> {
+        self.iter().map(|body| body.to_body()).collect()
+    }
+
+    /// Keeps only the bodies whose entry in `keep` is `true`, in order.
+    pub fn retain(&mut self, keep: &[bool]) {
+        fn retain<T>(buffer: &mut Vec<T>, keep: &[bool]) {
+            let mut index = 0;
+            buffer.retain(|_| {
+                index += 1;
+                keep[index - 1]
+            });
+        }
+        retain(&mut self.pos, keep);
+        retain(&mut self.velocity, keep);
+        retain(&mut self.acceleration, keep);
+        retain(&mut self.mass, keep);
+        retain(&mut self.physical_radius, keep);
+        retain(&mut self.kind, keep);
+        retain(&mut self.radius, keep);
+        retain(&mut self.color, keep);
+        self.massive.clear();
+        self.massive
+            .extend((0..self.len()).filter(|&i| self.kind[i] == Kind::Massive));
+    }
+
+    pub fn is_massive(&self, index: usize) -> bool {
+        self.kind[index] == Kind::Massive
+    }
+
+    /// Centre of mass of every body, or the origin if they have no mass.
+    pub fn barycentre(&self) -> Cartesian<F, D, Meter> {
+        Cartesian::from_vector(self.mass_weighted_mean(&self.pos))
+    }
+
+    /// Velocity of the centre of mass of every body, or zero if they have no mass.
+    pub fn barycentre_velocity(&self) -> Velocity<Cartesian<F, D, Meter>, D, 1> {
+        Velocity::from_vector(self.mass_weighted_mean(&self.velocity))
+    }
+
+    fn mass_weighted_mean(&self, buffer: &[SVector<F, D>]) -> SVector<F, D> {
+        let total = self
+            .mass
+            .iter()
+            .fold(F::zero(), |total, &mass| total + mass);
+        if total <= F::zero() {
+            return SVector::zeros();
+        }
+        let weighted = buffer
+            .iter()
+            .zip(self.mass.iter())
+            .fold(SVector::zeros(), |sum, (value, &mass)| sum + value * mass);
+        weighted / total
+    }
+
+    /// Advances every velocity by the stored acceleration over `step_time`.
+    pub fn kick(&mut self, step_time: F) {
+        for (velocity, acceleration) in self.velocity.iter_mut().zip(self.acceleration.iter()) {
+            *velocity += acceleration * step_time;
+        }
+    }
+
+    /// Advances every position by the current velocity over `step_time`.
+    pub fn drift(&mut self, step_time: F) {
+        for (pos, velocity) in self.pos.iter_mut().zip(self.velocity.iter()) {
+            *pos += velocity * step_time;
+        }
+    }
+
+    /// Moves every body by `pos` and `velocity`, e.g. into the frame of one of them.
+    pub fn shift_by(
+        &mut self,
+        pos: &Cartesian<F, D, Meter>,
+        velocity: &Velocity<Cartesian<F, D, Meter>, D, 1>,
+    ) {
+        for (p, v) in self.pos.iter_mut().zip(self.velocity.iter_mut()) {
+            *p += pos.covariant();
+            *v += velocity.covariant();
+        }
+    }
+
+    /// Copies the positions and velocities into `snapshot`, reusing its buffers.
+    pub fn save(&self, snapshot: &mut Snapshot<F, D>) {
+        snapshot.pos.clone_from(&self.pos);
+        snapshot.velocity.clone_from(&self.velocity);
+    }
+
+    /// Puts back the positions and velocities saved in `snapshot`.
+    pub fn restore(&mut self, snapshot: &Snapshot<F, D>) {
+        self.pos.copy_from_slice(&snapshot.pos);
+        self.velocity.copy_from_slice(&snapshot.velocity);
+    }
+}
+
+impl<F: Float, const D: usize> FromIterator<AstroBody<Cartesian<F, D, Meter>, D, 1>>
+    for BodySet<F, D>
+{
+    fn from_iter<T: IntoIterator<Item = AstroBody<Cartesian<F, D, Meter>, D, 1>>>(
+        bodies: T,
+    ) -> Self {
+        let mut set = BodySet::new();
+        for body in bodies {
+            set.push(body);
+        }
+        set
+    }
+}
+
+impl<F: Float, const D: usize> From<Vec<AstroBody<Cartesian<F, D, Meter>, D, 1>>>
+    for BodySet<F, D>
+{
+    fn from(bodies: Vec<AstroBody<Cartesian<F, D, Meter>, D, 1>>) -> Self {
+        bodies.into_iter().collect()
+    }
+}
+
+/// A single body of a [`BodySet`], read with the typed quantities of an [`AstroBody`].
+#[derive(Debug, Copy, Clone)]
+pub struct BodyView<'a, F, const D: usize> {
+    set: &'a BodySet<F, D>,
+    index: usize,
+}
+
+impl<'a, F: Float, const D: usize> BodyView<'a, F, D> {
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    pub fn pos(&self) -> Cartesian<F, D, Meter> {
+        Cartesian::from_vector(self.set.pos[self.index])
+    }
+
+    pub fn velocity(&self) -> Velocity<Cartesian<F, D, Meter>, D, 1> {
+        Velocity::from_vector(self.set.velocity[self.index])
+    }
+
+    pub fn acceleration(&self) -> Acceleration<Cartesian<F, D, Meter>, D, 1> {
+        Acceleration::from_vector(self.set.acceleration[self.index])
+    }
+
+    /// Force from the last evaluation, or the acceleration for a test particle as for
+    /// [`AstroBody::force`].
+    pub fn force(&self) -> Force<Cartesian<F, D, Meter>, D, 1> {
+        let inertia = match self.kind() {
+            Kind::Massive => self.mass(),
+            Kind::Particle => F::one(),
+        };
+        Force::from_vector(self.set.acceleration[self.index] * inertia)
+    }
+
+    pub fn mass(&self) -> F {
+        self.set.mass[self.index]
+    }
+
+    pub fn physical_radius(&self) -> F {
+        self.set.physical_radius[self.index]
+    }
+
+    pub fn radius(&self) -> f32 {
+        self.set.radius[self.index]
+    }
+
+    pub fn kind(&self) -> Kind {
+        self.set.kind[self.index]
+    }
+
+    pub fn is_massive(&self) -> bool {
+        self.kind() == Kind::Massive
+    }
+
+    pub fn color(&self) -> Option<&'a str> {
+        self.set.color[self.index].as_deref()
+    }
+
+    /// Copies the body out of the set.
+    pub fn to_body(&self) -> AstroBody<Cartesian<F, D, Meter>, D, 1> {
+        AstroBody {
+            pos: self.pos(),
+            velocity: self.velocity(),
+            force: self.force(),
+            mass: self.mass(),
+            radius: self.radius(),
+            physical_radius: self.physical_radius(),
+            kind: self.kind(),
+            color: self.set.color[self.index].clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Ensures that bodies come out of a set as they went in.
+    #[test]
+    fn test_round_trip() {
+        let bodies: Vec<AstroBody<Cartesian<f64, 2, Meter>, 2, 1>> = vec![
+            AstroBody::new_dynamic(2., Cartesian::new(1., 2.), Velocity::new(3., 4.))
+                .set_color("red".to_string())
+                .set_physical_radius(0.5),
+            AstroBody::new_particle(Cartesian::new(-1., 0.), Velocity::new(0., 1.)),
+        ];
+        let mut set = BodySet::from(bodies.clone());
+        assert_eq!(set.len(), 2);
+        assert_eq!(set.get(0).color(), Some("red"));
+        assert!(!set.get(1).is_massive());
+        assert_eq!(set.massive, [0]);
+        for (body, view) in bodies.iter().zip(set.iter()) {
+            let copy = view.to_body();
+            assert_eq!(copy.pos, body.pos);
+            assert_eq!(copy.velocity.covariant(), body.velocity.covariant());
+            assert_eq!(copy.mass, body.mass);
+            assert_eq!(copy.physical_radius, body.physical_radius);
+            assert_eq!(copy.kind, body.kind);
+            assert_eq!(copy.color, body.color);
+        }
+
+        assert_eq!(set.barycentre(), Cartesian::new(1., 2.));
+        assert_eq!(
+            set.barycentre_velocity().covariant(),
+            Velocity::<Cartesian<f64, 2, Meter>, 2, 1>::new(3., 4.).covariant()
+        );
+        set.set(1, bodies[0].clone());
+        assert_eq!(set.massive, [0, 1]);
+        set.set(0, bodies[1].clone());
+        assert_eq!(set.massive, [1]);
+        set.retain(&[false, true]);
+        assert_eq!(set.len(), 1);
+        assert_eq!(set.get(0).pos(), bodies[0].pos);
+        assert_eq!(set.massive, [0]);
+    }
+}
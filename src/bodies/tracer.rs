@@ -0,0 +1,212 @@
+use crate::quantities::{Float, Tensor};
+use crate::units::measure::Measure;
+use crate::units::time::second::Second;
+use std::collections::VecDeque;
+
+/// How much of its past a body's trail shows, selected per planet in the config with e.g.
+/// `trail = 500` (positions) or `trail = "30 d"` (simulated time).
+#[derive(serde::Deserialize, Debug, Copy, Clone, PartialEq)]
+#[serde(
+    untagged,
+    expecting = "expected a number of positions or a duration such as \"30 d\""
+)]
+pub enum Trail {
+    /// At most this many positions.
+    Points(usize),
+    /// Positions from at most this long ago.
+    Window(Measure<Second>),
+}
+
+/// Recent positions of a body, newest first, to draw its trail.
+///
+/// The trail holds at most `max_points` positions, none older than `max_age` seconds of
+/// simulated time. Where it runs nearly straight, positions in between are dropped: a new
+/// position replaces the newest one as long as, seen from the last position kept before it, it
+/// stays within `tolerance` radians of the direction the trail left that position in. Gentle
+/// arcs therefore take few points and tight turns keep their detail, and the chords never stray
+/// far from the path however finely it is sampled.
+#[derive(Debug)]
+pub struct Tracer<X, const R: usize, const C: usize>
+where
+    X: Tensor<R, C>,
+{
+    pub pos: VecDeque<X>,
+    /// Simulated time each position was recorded at, in seconds.
+    pub time: VecDeque<f64>,
+    pub max_points: usize,
+    pub max_age: Option<f64>,
+    pub tolerance: f64,
+    /// The newest position as it was first recorded, before any replacement: the direction
+    /// from `pos[1]` to it is the one later positions must keep to replace the newest.
+    heading: Option<X>,
+}
+
+impl<X, const R: usize> Default for Tracer<X, R, 1>
+where
+    X: Tensor<R, 1>,
+{
+    fn default() -> Self {
+        Tracer {
+            pos: VecDeque::new(),
+            time: VecDeque::new(),
+            max_points: Self::DEFAULT_POINTS,
+            max_age: None,
+            tolerance: Self::DEFAULT_TOLERANCE,
+            heading: None,
+        }
+    }
+}
+
+impl<X, const R: usize> Tracer<X, R, 1>
+where
+    X: Tensor<R, 1>,
+{
+    pub const DEFAULT_POINTS: usize = 1_000;
+    /// About a degree.
+    pub const DEFAULT_TOLERANCE: f64 = 0.02;
+}
+
+impl<X, const R: usize> Tracer<X, R, 1>
+where
+    X: Tensor<R, 1> + Copy,
+    X::Value: Float,
+{
+    /// Keeps the part of the trail given by `trail`, the default number of positions otherwise.
+    pub fn with_trail(mut self, trail: Option<Trail>) -> Self {
+        match trail {
+            None => {}
+            Some(Trail::Points(points)) => self.max_points = points,
+            Some(Trail::Window(window)) => {
+                self.max_points = usize::MAX;
+                self.max_age = Some(window.value());
+            }
+        }
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.pos.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pos.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.pos.clear();
+        self.time.clear();
+        self.heading = None;
+    }
+
+    /// Records the body at `pos` at simulated `time`, and forgets what falls out of the trail.
+    pub fn push(&mut self, pos: X, time: f64) {
+        if self.len() >= 2 && self.is_straight(&pos) {
+            self.pos[0] = pos;
+            self.time[0] = time;
+        } else {
+            self.pos.push_front(pos);
+            self.time.push_front(time);
+            self.heading = Some(pos);
+        }
+        let cutoff = self.max_age.map_or(f64::NEG_INFINITY, |age| time - age);
+        while self.len() > self.max_points.max(1)
+            || self.time.back().is_some_and(|&oldest| oldest < cutoff)
+        {
+            self.pos.pop_back();
+            self.time.pop_back();
+        }
+    }
+
+    /// Whether `pos` continues the trail from the position before the newest one in nearly the
+    /// direction it first left that position in, so that the newest one can go.
+    fn is_straight(&self, pos: &X) -> bool {
+        let Some(heading) = &self.heading else {
+            return false;
+        };
+        let before = self.pos[1].covariant();
+        let (kept, new) = (heading.covariant() - before, pos.covariant() - before);
+        if kept.norm().as_f64() == 0. || new.norm().as_f64() == 0. {
+            return true;
+        }
+        kept.angle(&new).as_f64() < self.tolerance
+    }
+
+    /// Positions with their age as a fraction of the trail from 0 (newest) to 1 (oldest kept),
+    /// e.g. to fade the trail out.
+    pub fn faded(&self) -> impl Iterator<Item = (&X, f64)> {
+        let newest = self.time.front().copied().unwrap_or_default();
+        let span = match self.max_age {
+            Some(age) => age,
+            None => newest - self.time.back().copied().unwrap_or_default(),
+        };
+        self.pos
+            .iter()
+            .zip(self.time.iter())
+            .map(move |(pos, time)| {
+                let age = if span > 0. {
+                    (newest - time) / span
+                } else {
+                    0.
+                };
+                (pos, age.clamp(0., 1.))
+            })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::quantities::spatial::Cartesian;
+    use crate::units::length::meter::Meter;
+
+    /// Ensures that straight stretches shrink to their ends, curves keep their points, and the
+    /// trail stays within its length and time window.
+    #[test]
+    fn test_bounded_and_decimated() {
+        let mut tracer: Tracer<Cartesian<f32, 2, Meter>, 2, 1> = Tracer::default();
+        for step in 0..100 {
+            tracer.push(Cartesian::new(step as f32, 2. * step as f32), step as f64);
+        }
+        assert_eq!(tracer.len(), 2);
+        assert_eq!(tracer.pos[0], Cartesian::new(99., 198.));
+
+        // 20 points on a quarter circle turn by about 4.5° each
+        let mut tracer = Tracer::default().with_trail(Some(Trail::Points(50)));
+        let circle = |step: usize| {
+            let angle = step as f32 * std::f32::consts::TAU / 80.;
+            Cartesian::<f32, 2, Meter>::new(angle.cos(), angle.sin())
+        };
+        for step in 0..20 {
+            tracer.push(circle(step), step as f64);
+        }
+        assert_eq!(tracer.len(), 20);
+        for step in 20..200 {
+            tracer.push(circle(step), step as f64);
+        }
+        assert_eq!(tracer.len(), 50);
+
+        // A year of daily samples turns by 1° a step, less than the tolerance, yet the chords
+        // still follow the circle
+        let mut tracer = Tracer::default();
+        let day = |step: usize| {
+            let angle = step as f32 * std::f32::consts::TAU / 365.;
+            Cartesian::<f32, 2, Meter>::new(angle.cos(), angle.sin())
+        };
+        for step in 0..365 {
+            tracer.push(day(step), step as f64);
+        }
+        assert!(tracer.len() > 50, "{} points", tracer.len());
+        for pair in tracer.pos.make_contiguous().windows(2) {
+            let middle = (pair[0].covariant() + pair[1].covariant()) / 2.;
+            assert!(middle.norm() > 0.999, "chord {:?} cuts the circle", pair);
+        }
+
+        let mut tracer = Tracer::default().with_trail(Some(Trail::Window(Measure::Bare(10.))));
+        for step in 0..200 {
+            tracer.push(circle(step), step as f64);
+        }
+        assert_eq!(tracer.time.back(), Some(&189.));
+        let ages: Vec<f64> = tracer.faded().map(|(_, age)| age).collect();
+        assert_eq!((ages[0], ages[ages.len() - 1]), (0., 1.));
+    }
+}
@@ -0,0 +1,296 @@
+use anisa_rs::quantities::spatial::Cartesian;
+use anisa_rs::units::length::meter::Meter;
+use anisa_rs::GravConst;
+use nannou::geom::{pt2, Point2};
+use std::fmt;
+use std::str::FromStr;
+
+/// How the distance from the centre of the view maps to the distance from the centre of the
+/// window, selected with e.g. `--radial log`.
+///
+/// Directions are kept either way. The nonlinear mappings squeeze outer orbits so that they fit
+/// on screen with inner ones, e.g. from Mercury to Neptune.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub enum Radial {
+    /// Proportional to the distance.
+    #[default]
+    Linear,
+    /// Linear close to the centre, logarithmic beyond [`Camera::KNEE`] pixels.
+    Log,
+    /// The distance to this power, e.g. `power:0.5` for its square root.
+    Power(f64),
+}
+
+impl Radial {
+    /// The next mapping to switch to from the keyboard.
+    pub fn next(self) -> Self {
+        match self {
+            Radial::Linear => Radial::Log,
+            Radial::Log => Radial::Power(0.5),
+            Radial::Power(_) => Radial::Linear,
+        }
+    }
+}
+
+impl fmt::Display for Radial {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Radial::Linear => write!(f, "linear"),
+            Radial::Log => write!(f, "log"),
+            Radial::Power(exponent) => write!(f, "power:{exponent}"),
+        }
+    }
+}
+
+impl FromStr for Radial {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "linear" => Ok(Radial::Linear),
+            "log" => Ok(Radial::Log),
+            radial => {
+                let exponent = radial
+                    .strip_prefix("power:")
+                    .and_then(|exponent| exponent.trim().parse::<f64>().ok())
+                    .ok_or(format!(
+                        "unknown radial mapping `{s}`, expected linear, log or power:<exponent>"
+                    ))?;
+                if exponent > 0. && exponent.is_finite() {
+                    Ok(Radial::Power(exponent))
+                } else {
+                    Err(format!("the exponent of `{s}` must be positive"))
+                }
+            }
+        }
+    }
+}
+
+/// Maps positions on the plane of the view, in meters, to window coordinates, in pixels from
+/// the centre of the window.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Camera {
+    /// Meters per pixel, at the centre of the window for nonlinear mappings.
+    pub scale: f64,
+    /// Position shown at the centre of the window.
+    pub centre: (f64, f64),
+    pub radial: Radial,
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Camera {
+            scale: Self::DEFAULT_SCALE,
+            centre: (0., 0.),
+            radial: Radial::Linear,
+        }
+    }
+}
+
+impl Camera {
+    /// 100 pixels per au.
+    pub const DEFAULT_SCALE: f64 = GravConst::DIST_AU / 100.;
+    /// Distance from the centre of the window, in pixels, around which the nonlinear mappings
+    /// start to squeeze distances.
+    pub const KNEE: f64 = 100.;
+    /// Closest that two distance rings get, in pixels.
+    const RING_GAP: f64 = 40.;
+
+    /// Distance from the centre of the window, in pixels, of what is `distance` meters from
+    /// the centre of the view.
+    pub fn pixels_at(&self, distance: f64) -> f64 {
+        let knee = Self::KNEE * self.scale;
+        match self.radial {
+            Radial::Linear => distance / self.scale,
+            Radial::Log => Self::KNEE * (distance / knee).ln_1p(),
+            Radial::Power(exponent) => Self::KNEE * (distance / knee).powf(exponent),
+        }
+    }
+
+    /// Inverse of [`Camera::pixels_at`].
+    pub fn distance_at(&self, pixels: f64) -> f64 {
+        let knee = Self::KNEE * self.scale;
+        match self.radial {
+            Radial::Linear => pixels * self.scale,
+            Radial::Log => knee * (pixels / Self::KNEE).exp_m1(),
+            Radial::Power(exponent) => knee * (pixels / Self::KNEE).powf(1. / exponent),
+        }
+    }
+
+    pub fn screen_position(&self, pos: &Cartesian<f64, 2, Meter>) -> Point2 {
+        let (x, y) = (
+            pos.horizontal() - self.centre.0,
+            pos.vertical() - self.centre.1,
+        );
+        let distance = x.hypot(y);
+        if distance == 0. {
+            return pt2(0., 0.);
+        }
+        let ratio = self.pixels_at(distance) / distance;
+        pt2((x * ratio) as f32, (y * ratio) as f32)
+    }
+
+    pub fn world_position(&self, point: Point2) -> Cartesian<f64, 2, Meter> {
+        let (x, y) = (point.x as f64, point.y as f64);
+        let pixels = x.hypot(y);
+        let ratio = if pixels == 0. {
+            0.
+        } else {
+            self.distance_at(pixels) / pixels
+        };
+        Cartesian::new(self.centre.0 + x * ratio, self.centre.1 + y * ratio)
+    }
+
+    /// Zooms in by `factor` (out below 1), keeping what is under `cursor` in place.
+    pub fn zoom_at(&mut self, factor: f64, cursor: Point2) {
+        let anchor = self.world_position(cursor);
+        self.scale /= factor;
+        let moved = self.world_position(cursor);
+        self.centre.0 += anchor.horizontal() - moved.horizontal();
+        self.centre.1 += anchor.vertical() - moved.vertical();
+    }
+
+    /// Moves the view along with a drag from `from` to `to`, keeping what was under the cursor
+    /// under it.
+    pub fn pan(&mut self, from: Point2, to: Point2) {
+        let (anchor, moved) = (self.world_position(from), self.world_position(to));
+        self.centre.0 += anchor.horizontal() - moved.horizontal();
+        self.centre.1 += anchor.vertical() - moved.vertical();
+    }
+
+    /// A round length (1, 2 or 5 times a power of ten) of at most `max_pixels` on screen, as
+    /// its length in pixels and a label in au, or in km below a tenth of an au.
+    pub fn scale_bar(&self, max_pixels: f64) -> (f32, String) {
+        let max = max_pixels * self.scale;
+        let (unit, name) = unit_for(max);
+        let max = max / unit;
+        let magnitude = 10f64.powf(max.log10().floor());
+        let length = [5., 2., 1.]
+            .into_iter()
+            .map(|mantissa| mantissa * magnitude)
+            .find(|&length| length <= max)
+            .unwrap_or(magnitude);
+        (
+            (length * unit / self.scale) as f32,
+            format!("{length} {name}"),
+        )
+    }
+
+    /// Round distances from the centre of the view to draw rings at, as their radius in pixels
+    /// and a label, out to `max_pixels` and at least [`Camera::RING_GAP`] pixels apart.
+    ///
+    /// Powers of ten come first, then 5 and 2 times a power of ten where they fit in between.
+    pub fn rings(&self, max_pixels: f64) -> Vec<(f32, String)> {
+        let (min, max) = (
+            self.distance_at(Self::RING_GAP / 2.),
+            self.distance_at(max_pixels),
+        );
+        let mut rings: Vec<(f32, String)> = Vec::new();
+        for mantissa in [1., 5., 2.] {
+            for (unit, name) in [unit_for(0.), unit_for(f64::INFINITY)] {
+                let exponents =
+                    (min / unit).log10().floor() as i32..=(max / unit).log10().ceil() as i32;
+                for exponent in exponents {
+                    let length = mantissa * 10f64.powi(exponent);
+                    let distance = length * unit;
+                    let pixels = self.pixels_at(distance);
+                    let crowded = rings
+                        .iter()
+                        .any(|&(ring, _)| (pixels - ring as f64).abs() < Self::RING_GAP);
+                    if distance < min
+                        || pixels > max_pixels
+                        || crowded
+                        || unit_for(distance).0 != unit
+                    {
+                        continue;
+                    }
+                    // Rounds away the error of the powers of ten, e.g. 0.30000000000000004
+                    let length = format!("{length:.6}");
+                    let length = length.trim_end_matches('0').trim_end_matches('.');
+                    rings.push((pixels as f32, format!("{length} {name}")));
+                }
+            }
+        }
+        rings.sort_by(|a, b| a.0.total_cmp(&b.0));
+        rings
+    }
+
+    /// Current scale for display, e.g. `0.01 au/px`, with the radial mapping if nonlinear.
+    pub fn describe(&self) -> String {
+        let scale = if self.scale >= 1e-3 * GravConst::DIST_AU {
+            format!("{:.3} au/px", self.scale / GravConst::DIST_AU)
+        } else {
+            format!("{:.3e} km/px", self.scale / 1e3)
+        };
+        match self.radial {
+            Radial::Linear => scale,
+            radial => format!("{scale} at the centre, {radial}"),
+        }
+    }
+}
+
+/// Unit to show `distance` meters in, as its length in meters and its name: au, or km below a
+/// tenth of an au.
+fn unit_for(distance: f64) -> (f64, &'static str) {
+    if distance >= 0.1 * GravConst::DIST_AU {
+        (GravConst::DIST_AU, "au")
+    } else {
+        (1e3, "km")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use anisa_rs::quantities::Tensor;
+
+    /// Ensures that zooming keeps the point under the cursor still, and the scale bar picks a
+    /// round length that fits.
+    #[test]
+    fn test_zoom_and_scale_bar() {
+        let mut camera = Camera::default();
+        let cursor = pt2(120., -40.);
+        let before = camera.world_position(cursor);
+        camera.zoom_at(3., cursor);
+        assert!((camera.world_position(cursor) - before).covariant().norm() < 1e-3);
+        assert!((camera.scale * 3. / Camera::DEFAULT_SCALE - 1.).abs() < 1e-12);
+
+        let (pixels, label) = Camera::default().scale_bar(150.);
+        assert_eq!((pixels.round(), label.as_str()), (100., "1 au"));
+        let (pixels, label) = Camera::default().scale_bar(1.);
+        assert_eq!(label, "1000000 km");
+        assert!(pixels <= 1.);
+    }
+
+    /// Ensures that the nonlinear mappings invert, and that their rings are round distances
+    /// spread out over the window.
+    #[test]
+    fn test_radial() {
+        assert_eq!("power:0.5".parse(), Ok(Radial::Power(0.5)));
+        assert_eq!("Log".parse(), Ok(Radial::Log));
+        assert!("power:-1".parse::<Radial>().is_err());
+        assert!("polar".parse::<Radial>().is_err());
+
+        for radial in [Radial::Log, Radial::Power(0.5)] {
+            let mut camera = Camera {
+                radial,
+                centre: (1e10, -2e10),
+                ..Default::default()
+            };
+            let pos = Cartesian::new(30. * GravConst::DIST_AU, 0.);
+            let point = camera.screen_position(&pos);
+            let back = camera.world_position(point);
+            assert!(((back - pos).covariant().norm() / GravConst::DIST_AU) < 1e-5);
+            // Neptune fits on screen (3000 px out when linear) with Mercury clear of the centre
+            assert!(point.x < 600.);
+            let mercury = Cartesian::new(1e10 + 0.4 * GravConst::DIST_AU, -2e10);
+            assert!(camera.screen_position(&mercury).x > 20.);
+
+            camera.pan(pt2(10., 10.), pt2(200., -50.));
+            let rings = camera.rings(400.);
+            assert!(rings.windows(2).all(|w| w[1].0 - w[0].0 >= 40.));
+            assert!(rings.iter().any(|(_, label)| label == "1 au"));
+            assert!(rings.iter().any(|(_, label)| label == "10 au"));
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use crate::units::time::second::Second;
+
+/// Paces a simulation with a fixed step against real time, for interactive viewers.
+///
+/// Real time is scaled by the time `warp` and accumulated; every whole physics step it covers
+/// is run, and the remainder carries over to the next frame. The simulation therefore runs at
+/// the same speed whatever the frame rate, and every step has the same length.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Clock {
+    /// Simulated seconds per real second.
+    pub warp: f64,
+    /// Simulated seconds owed to the physics, less than a step after [`Clock::tick`].
+    accumulator: f64,
+}
+
+impl Clock {
+    /// Most steps run in a single frame. Time beyond that is dropped, so a warp the machine
+    /// cannot keep up with slows the simulation down instead of freezing the viewer.
+    pub const MAX_STEPS: usize = 1_000;
+
+    pub fn new(warp: f64) -> Self {
+        Clock {
+            warp,
+            accumulator: 0.,
+        }
+    }
+
+    /// Number of steps of `step_time` to run after `elapsed` real time.
+    pub fn tick(&mut self, elapsed: Second, step_time: Second) -> usize {
+        let (elapsed, step_time): (f64, f64) = (1. * elapsed, 1. * step_time);
+        if step_time <= 0. {
+            return 0;
+        }
+        self.accumulator += self.warp * elapsed;
+        let steps = (self.accumulator / step_time).floor().max(0.);
+        if steps >= Self::MAX_STEPS as f64 {
+            self.accumulator = 0.;
+            return Self::MAX_STEPS;
+        }
+        self.accumulator -= steps * step_time;
+        steps as usize
+    }
+
+    /// Multiplies the warp by `factor`, e.g. 2 to go twice as fast.
+    pub fn scale_warp(&mut self, factor: f64) {
+        self.warp *= factor;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::units::Unit;
+
+    /// Ensures that the number of steps depends on the real time elapsed, not the frame rate.
+    #[test]
+    fn test_steps_do_not_depend_on_frame_rate() {
+        let step_time = Second::new(7_000.);
+        let run = |frames: usize, fps: f64| {
+            // One day of simulated time per real second
+            let mut clock = Clock::new(86_400.);
+            (0..frames)
+                .map(|_| clock.tick(Second::new(1. / fps), step_time))
+                .sum::<usize>()
+        };
+        // 1.25 days in steps of 7000 s
+        assert_eq!(run(75, 60.), 15);
+        assert_eq!(run(180, 144.), 15);
+        assert_eq!(run(10, 8.), 15);
+
+        let mut clock = Clock::new(1e12);
+        assert_eq!(clock.tick(Second::new(1.), step_time), Clock::MAX_STEPS);
+        clock.scale_warp(0.);
+        assert_eq!(clock.tick(Second::new(1.), step_time), 0);
+    }
+}
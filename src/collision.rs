@@ -0,0 +1,254 @@
+use crate::bodies::{AstroBody, BodySet, Snapshot};
+use crate::quantities::spatial::{Cartesian, Velocity};
+use crate::quantities::{Float, Tensor};
+use crate::units::length::meter::Meter;
+use nalgebra::SVector;
+
+/// What happens when two bodies touch, selected from the config with e.g.
+/// `collisions = "merge"`. Without it, bodies pass through each other.
+#[derive(serde::Deserialize, serde::Serialize, Debug, Copy, Clone, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Outcome {
+    /// Perfectly inelastic: one body with the total mass and momentum, at the centre of mass.
+    Merge,
+    /// Perfectly elastic, along the line between the centres.
+    Bounce,
+    /// The lighter body is taken out of the simulation; the heavier one is unaffected.
+    Remove,
+}
+
+/// A collision between two bodies, as recorded by the [`Simulation`](crate::Simulation).
+///
+/// Indices are into the bodies as they were before the step the collision happened in. The
+/// survivor of a merge keeps the lower index.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Collision<F: Float, const D: usize> {
+    /// Time of contact, in seconds since the start.
+    pub time: F,
+    pub first: usize,
+    pub second: usize,
+    pub outcome: Outcome,
+    /// Index of the body taken out of the simulation, if any.
+    pub removed: Option<usize>,
+    /// Centre of mass of the two bodies at contact.
+    pub pos: Cartesian<F, D, Meter>,
+    /// Speed of the bodies relative to each other just before contact.
+    pub relative_speed: F,
+}
+
+/// Finds every pair of bodies that came within the sum of their physical radii during a step
+/// from `start` to `bodies`, and resolves them in the order they touched.
+///
+/// Bodies are assumed to move in a straight line over the step, so a pair that passes through
+/// each other within a single step is still caught. `time` is the simulated time at `start`.
+pub fn resolve<F: Float, const D: usize>(
+    start: &Snapshot<F, D>,
+    bodies: &mut BodySet<F, D>,
+    outcome: Outcome,
+    time: F,
+    step_time: F,
+) -> Vec<Collision<F, D>> {
+    let mut contacts = vec![];
+    for i in 0..bodies.len() {
+        for j in i + 1..bodies.len() {
+            let reach = bodies.physical_radius[i] + bodies.physical_radius[j];
+            if reach <= F::zero() {
+                continue;
+            }
+            let before = start.pos[j] - start.pos[i];
+            let after = bodies.pos[j] - bodies.pos[i];
+            if let Some(fraction) = first_contact(&before, &after, reach) {
+                contacts.push((fraction, i, j));
+            }
+        }
+    }
+    contacts.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+
+    let mut alive = vec![true; bodies.len()];
+    let mut collisions = vec![];
+    for (fraction, i, j) in contacts {
+        if !alive[i] || !alive[j] {
+            continue;
+        }
+        let at = |k: usize| {
+            let (from, to) = (start.pos[k], bodies.pos[k]);
+            from + (to - from) * fraction
+        };
+        let contact = [at(i), at(j)];
+        let masses = [bodies.mass[i], bodies.mass[j]];
+        let relative_velocity = bodies.velocity[j] - bodies.velocity[i];
+        let total = masses[0] + masses[1];
+        let mut collision = Collision {
+            time: time + step_time * fraction,
+            first: i,
+            second: j,
+            outcome,
+            removed: None,
+            pos: Cartesian::from_vector(if total > F::zero() {
+                (contact[0] * masses[0] + contact[1] * masses[1]) / total
+            } else {
+                (contact[0] + contact[1]) / F::cast_from(2.)
+            }),
+            relative_speed: relative_velocity.norm(),
+        };
+        match outcome {
+            Outcome::Merge => {
+                let merged = merge(&bodies.get(i).to_body(), &bodies.get(j).to_body());
+                bodies.set(i, merged);
+                alive[j] = false;
+                collision.removed = Some(j);
+            }
+            Outcome::Remove => {
+                let lighter = if masses[0] < masses[1] { i } else { j };
+                alive[lighter] = false;
+                collision.removed = Some(lighter);
+            }
+            Outcome::Bounce => {
+                let normal = (contact[1] - contact[0]).try_normalize(F::zero());
+                let approach = normal.map_or(F::zero(), |n| relative_velocity.dot(&n));
+                // Bodies already moving apart have bounced before, or only grazed each other
+                if approach >= F::zero() || total <= F::zero() {
+                    continue;
+                }
+                let normal = normal.unwrap();
+                let remaining = step_time * (F::one() - fraction);
+                for (k, at, share) in [
+                    (i, contact[0], masses[1] / total),
+                    (j, contact[1], -masses[0] / total),
+                ] {
+                    let velocity =
+                        bodies.velocity[k] + normal * (F::cast_from(2.) * share * approach);
+                    bodies.velocity[k] = velocity;
+                    bodies.pos[k] = at + velocity * remaining;
+                }
+            }
+        }
+        collisions.push(collision);
+    }
+
+    bodies.retain(&alive);
+    collisions
+}
+
+/// Fraction of the step, in [0, 1], at which a separation moving linearly from `before` to
+/// `after` first shrinks to `reach`, if it does.
+fn first_contact<F: Float, const D: usize>(
+    before: &SVector<F, D>,
+    after: &SVector<F, D>,
+    reach: F,
+) -> Option<F> {
+    if before.norm() <= reach {
+        return Some(F::zero());
+    }
+    // Smallest root of |before + change s|² = reach²
+    let change = after - before;
+    let (a, b) = (change.norm_squared(), before.dot(&change));
+    let discriminant = b * b - a * (before.norm_squared() - reach * reach);
+    if a <= F::zero() || discriminant < F::zero() {
+        return None;
+    }
+    let fraction = (-b - discriminant.sqrt()) / a;
+    (F::zero()..=F::one())
+        .contains(&fraction)
+        .then_some(fraction)
+}
+
+/// One body with the total mass and momentum of `a` and `b`, at their centre of mass and with
+/// their combined volume. It keeps the colour and drawing size of the heavier one.
+fn merge<F: Float, const D: usize>(
+    a: &AstroBody<Cartesian<F, D, Meter>, D, 1>,
+    b: &AstroBody<Cartesian<F, D, Meter>, D, 1>,
+) -> AstroBody<Cartesian<F, D, Meter>, D, 1> {
+    let (major, minor) = if a.mass >= b.mass { (a, b) } else { (b, a) };
+    let mass = a.mass + b.mass;
+    let weighted = |x: &SVector<F, D>, y: &SVector<F, D>| {
+        if mass > F::zero() {
+            (x * a.mass + y * b.mass) / mass
+        } else {
+            (x + y) / F::cast_from(2.)
+        }
+    };
+    AstroBody {
+        pos: Cartesian::from_vector(weighted(a.pos.covariant(), b.pos.covariant())),
+        velocity: Velocity::from_vector(weighted(a.velocity.covariant(), b.velocity.covariant())),
+        mass,
+        physical_radius: (a.physical_radius.powi(3) + b.physical_radius.powi(3)).cbrt(),
+        radius: major.radius.max(minor.radius),
+        ..major.clone()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Two bodies of radius 1 heading at each other fast enough to cross within one step.
+    fn head_on() -> BodySet<f64, 2> {
+        BodySet::from(vec![
+            AstroBody::new_dynamic(3., Cartesian::new(-10., 0.), Velocity::new(10., 0.))
+                .set_physical_radius(1.),
+            AstroBody::new_dynamic(1., Cartesian::new(10., 0.5), Velocity::new(-20., 0.))
+                .set_physical_radius(1.),
+        ])
+    }
+
+    /// The state of `bodies`, and `bodies` moved in a straight line over `step_time` as an
+    /// integrator would without gravity.
+    fn drift(bodies: &BodySet<f64, 2>, step_time: f64) -> (Snapshot<f64, 2>, BodySet<f64, 2>) {
+        let mut start = Snapshot::default();
+        bodies.save(&mut start);
+        let mut moved = bodies.clone();
+        moved.drift(step_time);
+        (start, moved)
+    }
+
+    /// Ensures that bodies crossing within a step merge, conserving mass and momentum.
+    #[test]
+    fn test_merge_conserves_momentum() {
+        let (start, mut bodies) = drift(&head_on(), 1.);
+        // Both ends of the step are far apart: only the crossing check catches it
+        assert!((bodies.pos[1] - bodies.pos[0]).norm() > 2.);
+
+        let collisions = resolve(&start, &mut bodies, Outcome::Merge, 0., 1.);
+        assert_eq!(collisions.len(), 1);
+        assert_eq!(collisions[0].removed, Some(1));
+        // The centres are 2 apart when 20 - sqrt(4 - 0.5²) of the initial gap has closed
+        let contact = (20. - 3.75f64.sqrt()) / 30.;
+        assert!((collisions[0].time - contact).abs() < 1e-12);
+        assert!((collisions[0].relative_speed - 30.).abs() < 1e-12);
+        assert_eq!(bodies.len(), 1);
+        assert_eq!(bodies.mass[0], 4.);
+        assert_eq!(bodies.velocity[0], SVector::<f64, 2>::new(2.5, 0.));
+        assert!((bodies.physical_radius[0] - 2f64.cbrt()).abs() < 1e-12);
+    }
+
+    /// Ensures that a bounce keeps momentum and kinetic energy, and leaves the bodies apart.
+    #[test]
+    fn test_bounce_is_elastic() {
+        let initial = head_on();
+        let (start, mut bodies) = drift(&initial, 1.);
+        let momentum = |bodies: &BodySet<f64, 2>| {
+            bodies
+                .iter()
+                .map(|b| b.velocity().covariant() * b.mass())
+                .sum::<SVector<f64, 2>>()
+        };
+        let energy = |bodies: &BodySet<f64, 2>| {
+            bodies
+                .iter()
+                .map(|b| 0.5 * b.mass() * b.velocity().covariant().norm_squared())
+                .sum::<f64>()
+        };
+
+        let collisions = resolve(&start, &mut bodies, Outcome::Bounce, 0., 1.);
+        assert_eq!(collisions.len(), 1);
+        assert_eq!(bodies.len(), 2);
+        assert!((momentum(&bodies) - momentum(&initial)).norm() < 1e-12);
+        assert!((energy(&bodies) - energy(&initial)).abs() < 1e-9);
+        let separation = bodies.pos[1] - bodies.pos[0];
+        assert!(separation.x > 0.);
+        // Moving apart, so the next step does not bounce them again
+        let (start, mut bodies) = drift(&bodies, 0.01);
+        assert!(resolve(&start, &mut bodies, Outcome::Bounce, 1., 0.01).is_empty());
+    }
+}
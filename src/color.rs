@@ -0,0 +1,109 @@
+use palette::{Srgb, Srgba, WithAlpha};
+
+/// Parses a color from the config: a CSS name (`"steelblue"`) or a hex code (`"#4682b4"`, or
+/// `"#4682b480"` with an alpha channel), case insensitive.
+pub fn parse(color: &str) -> Option<Srgba<u8>> {
+    let color = color.trim().to_lowercase();
+    let Some(hex) = color.strip_prefix('#') else {
+        return palette::named::from_str(&color).map(|rgb| rgb.with_alpha(u8::MAX));
+    };
+    // Integer parsing would also take a sign in front of each component
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let (rgb, alpha) = match hex.len() {
+        8 => (&hex[..6], u8::from_str_radix(&hex[6..], 16).ok()?),
+        3 | 6 => (hex, u8::MAX),
+        _ => return None,
+    };
+    rgb.parse::<Srgb<u8>>()
+        .ok()
+        .map(|rgb| rgb.with_alpha(alpha))
+}
+
+/// Perceptually uniform colormaps, to color bodies by some quantity, selected with e.g.
+/// `--colormap magma`.
+#[derive(serde::Deserialize, clap::ValueEnum, Default, Debug, Copy, Clone, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Colormap {
+    #[default]
+    Viridis,
+    Magma,
+    Inferno,
+    Plasma,
+    /// Also readable with color vision deficiencies.
+    Cividis,
+}
+
+impl Colormap {
+    /// Evenly spaced samples of the colormaps from matplotlib, as `0xrrggbb`.
+    fn stops(&self) -> &'static [u32] {
+        match self {
+            Colormap::Viridis => &[
+                0x440154, 0x482878, 0x3e4989, 0x31688e, 0x26828e, 0x1f9e89, 0x35b779, 0x6ece58,
+                0xb5de2b, 0xfde725,
+            ],
+            Colormap::Magma => &[
+                0x000004, 0x180f3d, 0x440f76, 0x721f81, 0x9e2f7f, 0xcd4071, 0xf1605d, 0xfd9668,
+                0xfeca8d, 0xfcfdbf,
+            ],
+            Colormap::Inferno => &[
+                0x000004, 0x1b0c41, 0x4a0c6b, 0x781c6d, 0xa52c60, 0xcf4446, 0xed6925, 0xfb9b06,
+                0xf7d13d, 0xfcffa4,
+            ],
+            Colormap::Plasma => &[
+                0x0d0887, 0x46039f, 0x7201a8, 0x9c179e, 0xbd3786, 0xd8576b, 0xed7953, 0xfb9f3a,
+                0xfdca26, 0xf0f921,
+            ],
+            Colormap::Cividis => &[
+                0x00224e, 0x123570, 0x3b496c, 0x575d6d, 0x707173, 0x8a8678, 0xa59c74, 0xc3b369,
+                0xe1cc55, 0xfee838,
+            ],
+        }
+    }
+
+    /// Color at `t` from 0 to 1, clamped, interpolating linearly between the samples.
+    pub fn sample(&self, t: f64) -> Srgb<u8> {
+        let stops = self.stops();
+        let t = if t.is_nan() { 0. } else { t.clamp(0., 1.) };
+        let position = t * (stops.len() - 1) as f64;
+        let index = (position.floor() as usize).min(stops.len() - 2);
+        let fraction = position - index as f64;
+        let (from, to) = (Srgb::from(stops[index]), Srgb::from(stops[index + 1]));
+        let mix = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * fraction).round() as u8;
+        Srgb::new(
+            mix(from.red, to.red),
+            mix(from.green, to.green),
+            mix(from.blue, to.blue),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Ensures that CSS names and every hex format parse, and anything else does not.
+    #[test]
+    fn test_parse() {
+        assert_eq!(parse("SteelBlue"), Some(Srgba::new(70, 130, 180, 255)));
+        assert_eq!(parse("#4682b4"), Some(Srgba::new(70, 130, 180, 255)));
+        assert_eq!(parse("#4682B480"), Some(Srgba::new(70, 130, 180, 128)));
+        assert_eq!(parse("#fff"), Some(Srgba::new(255, 255, 255, 255)));
+        for invalid in ["bleu", "#12345", "4682b4", "#+1+2+3", "#4682b4zz"] {
+            assert_eq!(parse(invalid), None, "{invalid}");
+        }
+    }
+
+    /// Ensures that colormaps run from their first to their last sample.
+    #[test]
+    fn test_colormap_ends() {
+        assert_eq!(Colormap::Viridis.sample(0.), Srgb::new(0x44, 0x01, 0x54));
+        assert_eq!(Colormap::Viridis.sample(1.), Srgb::new(0xfd, 0xe7, 0x25));
+        assert_eq!(Colormap::Magma.sample(2.), Colormap::Magma.sample(1.));
+        assert_eq!(
+            Colormap::Plasma.sample(f64::NAN),
+            Colormap::Plasma.sample(0.)
+        );
+    }
+}
@@ -0,0 +1,567 @@
+use crate::bodies::{AstroBody, Kind, Trail};
+use crate::collision::Outcome;
+use crate::color;
+use crate::gravity::Solver;
+use crate::integrator::Scheme;
+use crate::orbit::Orbit;
+use crate::quantities::spatial::{Cartesian, Velocity};
+use crate::quantities::Float;
+use crate::units::length::meter::Meter;
+use crate::units::mass::kilogram::Kilogram;
+use crate::units::measure::Measure;
+use crate::units::time::second::Second;
+use crate::units::velocity::mps::MeterPerSecond;
+use chrono::{NaiveDate, NaiveDateTime};
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::path::PathBuf;
+
+#[derive(serde::Deserialize, Debug, Clone)]
+pub struct Planet {
+    /// Name other planets can refer to as their `parent`.
+    pub name: Option<String>,
+    /// Name of the planet this one orbits, anywhere in the list; the first planet by default.
+    pub parent: Option<String>,
+    /// Mass, either with a unit (`"1 M_earth"`) or as a bare number in kilograms. Ignored for
+    /// test particles.
+    #[serde(default)]
+    pub mass: Measure<Kilogram>,
+    /// `"particle"` for a massless test particle, which feels gravity without being a source.
+    #[serde(default)]
+    pub kind: Kind,
+    /// Orbital speed around the host, either with a unit (`"29.78 km/s"`) or as a bare number in
+    /// meters per second. Ignored with an `orbit`.
+    #[serde(default)]
+    pub velocity: Measure<MeterPerSecond>,
+    /// Distance from the host, either with a unit (`"1 au"`) or as a bare number in meters.
+    #[serde(default)]
+    pub distance: Measure<Meter>,
+    /// Physical radius used for collisions, either with a unit (`"6371 km"`) or as a bare number
+    /// in meters. A point mass by default.
+    #[serde(default)]
+    pub radius: Measure<Meter>,
+    /// A CSS name (`"steelblue"`) or a hex code (`"#4682b4"`, `"#4682b480"` with alpha).
+    pub color: String,
+    /// How much of its past the viewer draws behind the planet: a number of positions (`500`)
+    /// or a duration (`"30 d"`). The last 1000 positions by default.
+    pub trail: Option<Trail>,
+    /// Orbit around `parent`, replacing `velocity`: `"circular"` or an eccentricity at
+    /// `distance`, or Keplerian elements replacing `distance` too.
+    pub orbit: Option<Orbit>,
+}
+
+#[derive(serde::Deserialize, Debug, Clone)]
+pub struct Config {
+    pub planets: Vec<Planet>,
+    #[serde(default)]
+    pub integrator: Scheme,
+    pub tolerance: Option<f64>,
+    /// Simulated time per physics step, either with a unit (`"1 h"`) or as a bare number in
+    /// seconds. One day by default.
+    pub step_time: Option<Measure<Second>>,
+    /// Date and time at t = 0, e.g. `"2024-03-20T03:06:00"`. J2000 (2000-01-01 12:00) by
+    /// default.
+    #[serde(default = "Config::default_epoch")]
+    pub epoch: NaiveDateTime,
+    #[serde(default)]
+    pub solver: Solver,
+    /// Number of spatial dimensions the binary simulates in, 2 or 3.
+    #[serde(default = "Config::default_dimensions")]
+    pub dimensions: usize,
+    /// Floating point type the binary simulates in.
+    #[serde(default)]
+    pub precision: Precision,
+    /// Plummer softening length, either with a unit (`"0.01 au"`) or as a bare number in
+    /// meters. Gravity is exact by default.
+    #[serde(default)]
+    pub softening: Measure<Meter>,
+    /// Distance below which pairs of bodies are regularized, either with a unit (`"0.1 au"`) or
+    /// as a bare number in meters. Off by default.
+    pub regularization: Option<Measure<Meter>>,
+    /// Number of threads to evaluate forces on, one per core by default.
+    pub threads: Option<usize>,
+    /// What happens when bodies touch; they pass through each other by default.
+    pub collisions: Option<Outcome>,
+}
+
+/// Floating point precision of the simulation, selected from the config with e.g.
+/// `precision = "f32"`. Rendering is always done in `f32`.
+#[derive(serde::Deserialize, Default, Debug, Copy, Clone, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Precision {
+    F32,
+    #[default]
+    F64,
+}
+
+impl Config {
+    fn default_dimensions() -> usize {
+        2
+    }
+
+    fn default_epoch() -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2000, 1, 1)
+            .and_then(|date| date.and_hms_opt(12, 0, 0))
+            .unwrap()
+    }
+
+    /// Builds the bodies of every planet, in config order.
+    ///
+    /// Planets form a hierarchy through their `parent`, the first planet by default, which can
+    /// come anywhere in the list. The `orbit` (or `distance` and `velocity`) of a planet places the
+    /// centre of mass of it and everything orbiting it relative to its parent, so that a moon
+    /// and its planet together orbit the star. Planets without an orbit are spread around their
+    /// parent in the x-y plane. The whole system is then moved to its centre of mass, at rest.
+    pub fn to_bodies<F: Float, const D: usize>(
+        &self,
+        grav: F,
+    ) -> Result<Vec<AstroBody<Cartesian<F, D, Meter>, D, 1>>, ConfigError> {
+        let first = self.planets.first().ok_or(ConfigError::NoPlanets)?;
+        if first.orbit.is_some() && first.parent.is_none() {
+            return Err(ConfigError::InvalidOrbit {
+                planet: first.label(0),
+                reason: "the first planet is the host and cannot orbit".to_string(),
+            });
+        }
+        for (index, planet) in self.planets.iter().enumerate() {
+            if color::parse(&planet.color).is_none() {
+                return Err(ConfigError::InvalidColor {
+                    planet: planet.label(index),
+                    color: planet.color.clone(),
+                });
+            }
+        }
+        let parents = self.parents()?;
+        let mut children = vec![Vec::new(); self.planets.len()];
+        for (index, parent) in parents.iter().enumerate() {
+            if let Some(parent) = parent {
+                children[*parent].push(index);
+            }
+        }
+
+        let mut bodies: Vec<AstroBody<Cartesian<F, D, Meter>, D, 1>> =
+            self.planets.iter().map(AstroBody::from).collect();
+        let angles = ndarray::Array::linspace(0., 360., self.planets.len() - 1);
+        let hierarchy = Hierarchy {
+            children: &children,
+            angles: angles.as_slice().unwrap_or_default(),
+            grav,
+        };
+        self.place(0, &hierarchy, &mut bodies)?;
+
+        let everything: Vec<usize> = (0..bodies.len()).collect();
+        let (_, pos, velocity) = centre_of_mass(&bodies, &everything);
+        for body in bodies.iter_mut() {
+            body.pos = body.pos - pos;
+            body.velocity = body.velocity - velocity;
+        }
+        Ok(bodies)
+    }
+
+    /// Index of the parent of every planet, `None` for the first one.
+    pub fn parents(&self) -> Result<Vec<Option<usize>>, ConfigError> {
+        for (index, planet) in self.planets.iter().enumerate() {
+            if let Some(name) = &planet.name {
+                if self.planets[..index]
+                    .iter()
+                    .any(|p| p.name.as_ref() == Some(name))
+                {
+                    return Err(ConfigError::DuplicateName(name.clone()));
+                }
+            }
+        }
+        let mut parents = Vec::with_capacity(self.planets.len());
+        for (index, planet) in self.planets.iter().enumerate() {
+            let parent = match &planet.parent {
+                None if index == 0 => None,
+                None => Some(0),
+                Some(name) => Some(
+                    self.planets
+                        .iter()
+                        .position(|p| p.name.as_ref() == Some(name))
+                        .ok_or_else(|| ConfigError::UnknownParent {
+                            planet: planet.label(index),
+                            parent: name.clone(),
+                        })?,
+                ),
+            };
+            parents.push(parent);
+        }
+        // Every chain of parents must end at the first planet
+        for start in 0..parents.len() {
+            let mut chain = vec![start];
+            while let Some(parent) = parents[*chain.last().unwrap()] {
+                if let Some(position) = chain.iter().position(|&index| index == parent) {
+                    return Err(ConfigError::ParentCycle(
+                        chain[position..]
+                            .iter()
+                            .chain([&parent])
+                            .map(|&index| self.planets[index].label(index))
+                            .collect(),
+                    ));
+                }
+                chain.push(parent);
+            }
+        }
+        Ok(parents)
+    }
+
+    /// Places everything orbiting planet `index` around it, with `index` at the origin, and
+    /// returns the planets placed including `index`.
+    fn place<F: Float, const D: usize>(
+        &self,
+        index: usize,
+        hierarchy: &Hierarchy<F>,
+        bodies: &mut [AstroBody<Cartesian<F, D, Meter>, D, 1>],
+    ) -> Result<Vec<usize>, ConfigError> {
+        let mut placed = vec![index];
+        for &child in hierarchy.children[index].iter() {
+            let system = self.place(child, hierarchy, bodies)?;
+            let (mass, pos, velocity) = centre_of_mass(bodies, &system);
+            let angle = hierarchy.angles[child - 1];
+            let (offset, speed) = match &self.planets[child].orbit {
+                None => self.planets[child].relative_state(&angle),
+                Some(orbit) => {
+                    // Same direction from the parent as without an orbit
+                    let orbit = orbit.elements(
+                        self.planets[child].distance,
+                        (angle + std::f64::consts::PI).to_degrees(),
+                    );
+                    let invalid = |reason| ConfigError::InvalidOrbit {
+                        planet: self.planets[child].label(child),
+                        reason,
+                    };
+                    orbit.validate().map_err(invalid)?;
+                    if D < 3 && !orbit.is_planar() {
+                        return Err(invalid("inclined orbits need `dimensions = 3`".to_string()));
+                    }
+                    orbit.state_vector(hierarchy.grav * (bodies[index].mass + mass))
+                }
+            };
+            for &member in system.iter() {
+                let body = &mut bodies[member];
+                body.pos = body.pos - pos + offset;
+                body.velocity = body.velocity - velocity + speed;
+            }
+            placed.extend(system);
+        }
+        Ok(placed)
+    }
+}
+
+/// What [`Config::place`] needs to know about the whole system.
+struct Hierarchy<'a, F> {
+    /// Indices of the planets orbiting each planet.
+    children: &'a [Vec<usize>],
+    /// Direction of every planet but the first from its parent, when not given by its orbit.
+    angles: &'a [f64],
+    grav: F,
+}
+
+/// Total mass, centre of mass and its velocity of the `members` of `bodies`. Massless members
+/// are centred on the first of them.
+fn centre_of_mass<F: Float, const D: usize>(
+    bodies: &[AstroBody<Cartesian<F, D, Meter>, D, 1>],
+    members: &[usize],
+) -> (
+    F,
+    Cartesian<F, D, Meter>,
+    Velocity<Cartesian<F, D, Meter>, D, 1>,
+) {
+    let mass = members
+        .iter()
+        .fold(F::zero(), |total, &member| total + bodies[member].mass);
+    if mass <= F::zero() {
+        let first = &bodies[members[0]];
+        return (mass, first.pos, first.velocity);
+    }
+    let (pos, velocity) = members.iter().fold(
+        (Cartesian::zero(), Velocity::zero()),
+        |(pos, velocity), &member| {
+            let body = &bodies[member];
+            (
+                pos + body.pos * (body.mass / mass),
+                velocity + body.velocity * (body.mass / mass),
+            )
+        },
+    );
+    (mass, pos, velocity)
+}
+
+impl Planet {
+    /// Name of the planet for messages, falling back to its position in the config.
+    pub fn label(&self, index: usize) -> String {
+        self.name
+            .clone()
+            .unwrap_or_else(|| format!("planet #{}", index + 1))
+    }
+
+    /// Position and velocity relative to the host from `distance` and `velocity`, at `angle`
+    /// around it.
+    fn relative_state<F: Float, const D: usize>(
+        &self,
+        angle: &f64,
+    ) -> (
+        Cartesian<F, D, Meter>,
+        Velocity<Cartesian<F, D, Meter>, D, 1>,
+    ) {
+        #[allow(deprecated)]
+        let here = Cartesian::with_magnitude(
+            F::cast_from(self.distance.value()),
+            F::cast_from(*angle),
+            Cartesian::zero(),
+        );
+        let velocity = Velocity::new_perpendicular_to(
+            F::cast_from(self.velocity.value()),
+            &here,
+            &Cartesian::zero(),
+            F::zero(),
+        );
+        (here.embed(), velocity.embed())
+    }
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(std::io::Error),
+    Toml(toml::de::Error),
+    NoPlanets,
+    Dimensions(usize),
+    UnknownParent {
+        planet: String,
+        parent: String,
+    },
+    /// Planets that orbit each other in a loop, the first one repeated at the end.
+    ParentCycle(Vec<String>),
+    DuplicateName(String),
+    InvalidColor {
+        planet: String,
+        color: String,
+    },
+    InvalidOrbit {
+        planet: String,
+        reason: String,
+    },
+    Threads(rayon::ThreadPoolBuildError),
+    /// `regularization` together with the Barnes–Hut solver, whose pull of a pair cannot be
+    /// taken back exactly.
+    RegularizedTree,
+}
+
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigError::Io(e) => write!(f, "Could not read config: {e}"),
+            ConfigError::Toml(e) => write!(f, "Invalid config: {e}"),
+            ConfigError::NoPlanets => write!(f, "Invalid config: no planets"),
+            ConfigError::Dimensions(dimensions) => write!(
+                f,
+                "Invalid config: dimensions must be 2 or 3, got {dimensions}"
+            ),
+            ConfigError::UnknownParent { planet, parent } => write!(
+                f,
+                "Invalid config: {planet} orbits unknown parent `{parent}`"
+            ),
+            ConfigError::ParentCycle(planets) => write!(
+                f,
+                "Invalid config: planets orbit each other in a loop: {}",
+                planets.join(" -> ")
+            ),
+            ConfigError::DuplicateName(name) => {
+                write!(f, "Invalid config: more than one planet is named `{name}`")
+            }
+            ConfigError::InvalidColor { planet, color } => write!(
+                f,
+                "Invalid config: color `{color}` of {planet} is neither a CSS color name nor \
+                 #rrggbb or #rrggbbaa"
+            ),
+            ConfigError::InvalidOrbit { planet, reason } => {
+                write!(f, "Invalid config: orbit of {planet}: {reason}")
+            }
+            ConfigError::Threads(e) => write!(f, "Could not start threads: {e}"),
+            ConfigError::RegularizedTree => write!(
+                f,
+                "Invalid config: regularization needs the direct solver, not barnes-hut"
+            ),
+        }
+    }
+}
+
+impl Error for ConfigError {}
+
+impl From<std::io::Error> for ConfigError {
+    fn from(value: std::io::Error) -> Self {
+        ConfigError::Io(value)
+    }
+}
+
+impl From<toml::de::Error> for ConfigError {
+    fn from(value: toml::de::Error) -> Self {
+        ConfigError::Toml(value)
+    }
+}
+
+pub fn read_config(file: PathBuf) -> Result<Config, ConfigError> {
+    Ok(toml::from_str(&fs::read_to_string(file)?)?)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::bodies::BodySet;
+    use crate::quantities::Tensor;
+    use crate::{GravConst, Simulation};
+
+    /// Distance of the second planet from the first after every hour for `years`, in au.
+    fn radii(orbit: &str, years: usize) -> Vec<f64> {
+        let config: Config = toml::from_str(&format!(
+            r#"
+            step_time = "1 h"
+            [[planets]]
+            mass = "1 M_sun"
+            color = "white"
+            [[planets]]
+            mass = "1 M_earth"
+            distance = "1 au"
+            orbit = {orbit}
+            color = "blue"
+            "#
+        ))
+        .unwrap();
+        let mut simulation = Simulation::<f64, 2>::from_config(&config).unwrap();
+        (0..24 * 365 * years)
+            .map(|_| {
+                simulation.step();
+                let bodies = simulation.bodies();
+                let separation = bodies.get(1).pos() - bodies.get(0).pos();
+                separation.covariant().magnitude() / GravConst::DIST_AU
+            })
+            .collect()
+    }
+
+    /// Ensures that a circular orbit keeps its radius, and an eccentric one starts at periapsis
+    /// and reaches the apoapsis of its eccentricity.
+    #[test]
+    fn test_orbit_from_distance() {
+        for radius in radii(r#""circular""#, 1) {
+            assert!((radius - 1.).abs() < 1e-5, "radius {radius} au");
+        }
+
+        // e = 0.5 from periapsis at 1 au: a = 2 au, apoapsis at 3 au after 1.41 years
+        let radii = radii("0.5", 2);
+        let (min, max) = radii.iter().fold((f64::INFINITY, 0f64), |(min, max), &r| {
+            (min.min(r), max.max(r))
+        });
+        assert!((min - 1.).abs() < 1e-3, "periapsis {min} au");
+        assert!((max - 3.).abs() < 1e-3, "apoapsis {max} au");
+    }
+
+    fn parse(planets: &str) -> Config {
+        toml::from_str(planets).unwrap()
+    }
+
+    /// Ensures that a bare velocity is in meters per second, like every other bare number, and
+    /// starts the planet perpendicular to its host.
+    #[test]
+    fn test_bare_velocity() {
+        let state = |velocity: &str| {
+            let config = parse(&format!(
+                r#"
+                [[planets]]
+                mass = 1.0
+                color = "white"
+                [[planets]]
+                distance = 1.5e11
+                velocity = {velocity}
+                color = "blue"
+                "#
+            ));
+            let bodies = BodySet::from(config.to_bodies::<f64, 2>(GravConst::G).unwrap());
+            (
+                *(bodies.get(1).pos() - bodies.get(0).pos()).covariant(),
+                *(bodies.get(1).velocity() - bodies.get(0).velocity()).covariant(),
+            )
+        };
+        let speed = |velocity: &str| state(velocity).1.norm();
+        assert!((speed("29780.0") - speed(r#""29.78 km/s""#)).abs() < 1e-9);
+        assert!((speed("29780.0") - 29780.).abs() < 1e-6);
+        let (separation, velocity) = state("29780.0");
+        assert!(separation.normalize().dot(&velocity.normalize()).abs() < 1e-12);
+    }
+
+    /// Ensures that a moon listed before its planet orbits it, the pair orbits the star through
+    /// their centre of mass, and the whole system starts at rest at the origin.
+    #[test]
+    fn test_hierarchy() {
+        let config = parse(
+            r#"
+            [[planets]]
+            name = "Sun"
+            mass = "1 M_sun"
+            color = "white"
+            [[planets]]
+            name = "Moon"
+            parent = "Earth"
+            mass = 7.342e22
+            distance = "384400 km"
+            orbit = "circular"
+            color = "white"
+            [[planets]]
+            name = "Earth"
+            mass = "1 M_earth"
+            distance = "1 au"
+            orbit = "circular"
+            color = "blue"
+            "#,
+        );
+        let bodies = BodySet::from(config.to_bodies::<f64, 2>(GravConst::G).unwrap());
+        let (sun, moon, earth) = (bodies.get(0), bodies.get(1), bodies.get(2));
+        assert!(bodies.barycentre().covariant().norm() < 1e-6);
+        assert!(bodies.barycentre_velocity().covariant().norm() < 1e-12);
+
+        let separation = (moon.pos() - earth.pos()).covariant().norm();
+        assert!((separation / 384_400e3 - 1.).abs() < 1e-12);
+        let speed = (moon.velocity() - earth.velocity()).covariant().norm();
+        let circular = (GravConst::G * (moon.mass() + earth.mass()) / separation).sqrt();
+        assert!((speed / circular - 1.).abs() < 1e-12);
+
+        let pair = (moon.pos() * moon.mass() + earth.pos() * earth.mass())
+            * (1. / (moon.mass() + earth.mass()));
+        let distance = (pair - sun.pos()).covariant().norm();
+        assert!((distance / GravConst::DIST_AU - 1.).abs() < 1e-12);
+    }
+
+    /// Ensures that unknown parents and loops of parents are reported.
+    #[test]
+    fn test_hierarchy_errors() {
+        let planets = |a: &str, b: &str| {
+            parse(&format!(
+                r#"
+                [[planets]]
+                name = "Sun"
+                color = "white"
+                [[planets]]
+                name = "A"
+                parent = "{a}"
+                color = "white"
+                [[planets]]
+                name = "B"
+                parent = "{b}"
+                color = "white"
+                "#
+            ))
+            .to_bodies::<f64, 2>(1.)
+            .unwrap_err()
+            .to_string()
+        };
+        assert_eq!(
+            planets("B", "A"),
+            "Invalid config: planets orbit each other in a loop: A -> B -> A"
+        );
+        assert_eq!(
+            planets("Sun", "C"),
+            "Invalid config: B orbits unknown parent `C`"
+        );
+    }
+}
@@ -0,0 +1,163 @@
+use crate::bodies::BodySet;
+use crate::gravity::Gravity;
+use crate::quantities::Float;
+use nalgebra::SVector;
+use std::marker::PhantomData;
+
+/// Conserved quantities of a set of bodies, used to judge how trustworthy a run is.
+///
+/// Energy and angular momentum are taken in the barycentric frame, so they do not change when
+/// every body is shifted into another inertial frame (e.g. the one the viewer locks onto).
+/// Everything is accumulated in `f64` whatever the precision `F` of the simulation, as products
+/// such as `m_1 m_2` overflow `f32` for stellar masses. Angular momentum is always a 3D vector,
+/// along the z axis in 2D.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Diagnostics<F, const D: usize> {
+    pub kinetic_energy: f64,
+    pub potential_energy: f64,
+    pub momentum: SVector<f64, D>,
+    pub angular_momentum: SVector<f64, 3>,
+    /// Sum of the magnitudes of the individual momenta, the scale momentum drift is relative to.
+    momentum_scale: f64,
+    /// Sum of the magnitudes of the individual angular momenta.
+    angular_momentum_scale: f64,
+    precision: PhantomData<F>,
+}
+
+/// Relative change of the conserved quantities between two [`Diagnostics`].
+#[derive(serde::Serialize, Default, Debug, Copy, Clone, PartialEq)]
+pub struct Drift {
+    pub energy: f64,
+    pub momentum: f64,
+    pub angular_momentum: f64,
+}
+
+impl<F: Float, const D: usize> Diagnostics<F, D> {
+    /// Measures `bodies` interacting through `gravity`.
+    ///
+    /// The potential energy is softened like the forces, except between regularized pairs.
+    pub fn measure(bodies: &BodySet<F, D>, gravity: &Gravity<F>) -> Self {
+        let grav = gravity.grav.as_f64();
+        let position = |i: usize| bodies.pos[i].map(|x| x.as_f64());
+        let velocity = |i: usize| bodies.velocity[i].map(|x| x.as_f64());
+        let mass = |i: usize| bodies.mass[i].as_f64();
+
+        let total_mass: f64 = (0..bodies.len()).map(mass).sum();
+        let (mut centre, mut momentum) = (SVector::<f64, D>::zeros(), SVector::<f64, D>::zeros());
+        for i in 0..bodies.len() {
+            centre += position(i) * mass(i);
+            momentum += velocity(i) * mass(i);
+        }
+        let (centre, centre_velocity) = if total_mass > 0. {
+            (centre / total_mass, momentum / total_mass)
+        } else {
+            (SVector::zeros(), SVector::zeros())
+        };
+
+        let mut diagnostics = Diagnostics {
+            kinetic_energy: 0.,
+            potential_energy: 0.,
+            momentum,
+            angular_momentum: SVector::zeros(),
+            momentum_scale: 0.,
+            angular_momentum_scale: 0.,
+            precision: PhantomData,
+        };
+        for i in 0..bodies.len() {
+            let (pos, vel) = (position(i) - centre, velocity(i) - centre_velocity);
+            let angular_momentum = embed(&pos).cross(&embed(&vel)) * mass(i);
+            diagnostics.kinetic_energy += 0.5 * mass(i) * vel.norm_squared();
+            diagnostics.angular_momentum += angular_momentum;
+            diagnostics.momentum_scale += mass(i) * velocity(i).norm();
+            diagnostics.angular_momentum_scale += angular_momentum.norm();
+            for j in i + 1..bodies.len() {
+                let softening = match gravity.regularized.contains(&(i, j)) {
+                    true => 0.,
+                    false => gravity.softening.as_f64(),
+                };
+                let distance_squared = (position(j) - position(i)).norm_squared();
+                if distance_squared > 0. {
+                    diagnostics.potential_energy -= grav * mass(i) * mass(j)
+                        / (distance_squared + softening * softening).sqrt();
+                }
+            }
+        }
+        diagnostics
+    }
+
+    /// Total kinetic and potential energy.
+    pub fn energy(&self) -> f64 {
+        self.kinetic_energy + self.potential_energy
+    }
+
+    /// Relative change since `start`.
+    ///
+    /// Momentum is compared to the sum of the individual momenta at `start`, as the total is
+    /// usually close to zero; the others to their own magnitude at `start`, falling back to the
+    /// same kind of sum when that is zero.
+    pub fn drift_since(&self, start: &Diagnostics<F, D>) -> Drift {
+        let energy_scale = match start.energy().abs() {
+            scale if scale > 0. => scale,
+            _ => start.kinetic_energy.abs() + start.potential_energy.abs(),
+        };
+        let angular_momentum_scale = match start.angular_momentum.norm() {
+            scale if scale > 0. => scale,
+            _ => start.angular_momentum_scale,
+        };
+        Drift {
+            energy: relative(self.energy() - start.energy(), energy_scale),
+            momentum: relative(
+                (self.momentum - start.momentum).norm(),
+                start.momentum_scale,
+            ),
+            angular_momentum: relative(
+                (self.angular_momentum - start.angular_momentum).norm(),
+                angular_momentum_scale,
+            ),
+        }
+    }
+}
+
+/// The first three components of `vector`, padded with zeros.
+fn embed<const D: usize>(vector: &SVector<f64, D>) -> SVector<f64, 3> {
+    SVector::from_fn(|k, _| vector.get(k).copied().unwrap_or(0.))
+}
+
+/// `change` relative to `scale`, or as is when there is nothing to compare to.
+fn relative(change: f64, scale: f64) -> f64 {
+    if scale > 0. {
+        change.abs() / scale
+    } else {
+        change.abs()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::bodies::AstroBody;
+    use crate::quantities::spatial::{Cartesian, Velocity};
+
+    /// Ensures that a circular binary has the textbook invariants, unchanged by a Galilean shift.
+    #[test]
+    fn test_circular_binary() {
+        // Equal masses 1 at distance 2 with G = 1: v = sqrt(G m / (4 r)) = 1/2 around the centre
+        let bodies = BodySet::from(vec![
+            AstroBody::new_dynamic(1., Cartesian::new(-1., 0.), Velocity::new(0., -0.5)),
+            AstroBody::new_dynamic(1., Cartesian::new(1., 0.), Velocity::new(0., 0.5)),
+        ]);
+        let start = Diagnostics::measure(&bodies, &Gravity::new(1.));
+        assert!((start.kinetic_energy - 0.25).abs() < 1e-12);
+        assert!((start.potential_energy + 0.5).abs() < 1e-12);
+        assert_eq!(start.angular_momentum, SVector::<f64, 3>::new(0., 0., 1.));
+        assert_eq!(start.momentum, SVector::<f64, 2>::zeros());
+
+        let mut shifted = bodies.clone();
+        shifted.shift_by(&Cartesian::new(3., -2.), &Velocity::new(0.25, 0.));
+        let drift = Diagnostics::measure(&shifted, &Gravity::new(1.)).drift_since(&start);
+        assert!(drift.energy < 1e-12);
+        assert!(drift.angular_momentum < 1e-12);
+        // The total momentum is not frame independent: 2 * 0.25 out of a scale of 2 * 0.5
+        assert!((drift.momentum - 0.5).abs() < 1e-12);
+    }
+}
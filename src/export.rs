@@ -0,0 +1,264 @@
+use crate::bodies::{BodySet, BodyView};
+use crate::collision::{Collision, Outcome};
+use crate::diagnostics::{Diagnostics, Drift};
+use crate::frames::{Frame, Transform};
+use crate::quantities::{Float, Tensor};
+use std::error::Error;
+use std::io::Write;
+
+/// State of a single body at a single step, as written by the [`Exporter`].
+///
+/// Positions are in meters, velocities in meters per second and time in seconds since the
+/// start of the run, in the precision `F` of the simulation. The `z` components are only
+/// written for 3D simulations.
+///
+/// Positions and velocities are in the frame of the [`Exporter`], see [`Exporter::with_frame`].
+#[derive(serde::Serialize, Debug, Clone, PartialEq)]
+pub struct BodyState<F = f64> {
+    pub time: F,
+    pub index: usize,
+    pub x: F,
+    pub y: F,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub z: Option<F>,
+    pub vx: F,
+    pub vy: F,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vz: Option<F>,
+    pub mass: F,
+}
+
+impl<F: Float> BodyState<F> {
+    /// State of `body` in the frame resolved to `transform`.
+    pub fn new<const D: usize>(
+        time: F,
+        body: &BodyView<F, D>,
+        transform: &Transform<F, D>,
+    ) -> Self {
+        let pos = transform.position(&body.pos());
+        let velocity = transform.velocity(&body.pos(), &body.velocity());
+        BodyState {
+            time,
+            index: body.index(),
+            x: pos.horizontal(),
+            y: pos.vertical(),
+            z: pos.covariant().get(2).copied(),
+            vx: velocity.horizontal(),
+            vy: velocity.vertical(),
+            vz: velocity.covariant().get(2).copied(),
+            mass: body.mass(),
+        }
+    }
+}
+
+/// Conserved quantities at a single step, as written by the [`Exporter`].
+///
+/// Drifts are relative to t = 0, see [`Diagnostics::drift_since`]. Only the `z` component of
+/// the angular momentum is written for 2D simulations.
+#[derive(serde::Serialize, Debug, Clone, PartialEq)]
+pub struct DiagnosticsState {
+    pub time: f64,
+    pub energy: f64,
+    pub momentum_x: f64,
+    pub momentum_y: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub momentum_z: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub angular_momentum_x: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub angular_momentum_y: Option<f64>,
+    pub angular_momentum_z: f64,
+    pub energy_drift: f64,
+    pub momentum_drift: f64,
+    pub angular_momentum_drift: f64,
+}
+
+impl DiagnosticsState {
+    pub fn new<F: Float, const D: usize>(
+        time: F,
+        diagnostics: &Diagnostics<F, D>,
+        drift: &Drift,
+    ) -> Self {
+        let planar = D < 3;
+        DiagnosticsState {
+            time: time.as_f64(),
+            energy: diagnostics.energy(),
+            momentum_x: diagnostics.momentum[0],
+            momentum_y: diagnostics.momentum[1],
+            momentum_z: diagnostics.momentum.get(2).copied(),
+            angular_momentum_x: (!planar).then_some(diagnostics.angular_momentum.x),
+            angular_momentum_y: (!planar).then_some(diagnostics.angular_momentum.y),
+            angular_momentum_z: diagnostics.angular_momentum.z,
+            energy_drift: drift.energy,
+            momentum_drift: drift.momentum,
+            angular_momentum_drift: drift.angular_momentum,
+        }
+    }
+}
+
+/// A single collision, as written by the [`Exporter`].
+///
+/// Indices are into the bodies as exported just before the collision.
+#[derive(serde::Serialize, Debug, Clone, PartialEq)]
+pub struct CollisionState<F = f64> {
+    pub time: F,
+    pub first: usize,
+    pub second: usize,
+    pub outcome: Outcome,
+    pub removed: Option<usize>,
+    pub x: F,
+    pub y: F,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub z: Option<F>,
+    pub relative_speed: F,
+}
+
+impl<F: Float> CollisionState<F> {
+    pub fn new<const D: usize>(collision: &Collision<F, D>) -> Self {
+        CollisionState {
+            time: collision.time,
+            first: collision.first,
+            second: collision.second,
+            outcome: collision.outcome,
+            removed: collision.removed,
+            x: collision.pos.horizontal(),
+            y: collision.pos.vertical(),
+            z: collision.pos.covariant().get(2).copied(),
+            relative_speed: collision.relative_speed,
+        }
+    }
+}
+
+/// Writes per-step body states to any combination of CSV and JSON Lines outputs, and
+/// optionally per-step diagnostics and collisions as CSV.
+pub struct Exporter {
+    frame: Frame,
+    csv: Option<csv::Writer<Box<dyn Write>>>,
+    jsonl: Option<Box<dyn Write>>,
+    diagnostics: Option<csv::Writer<Box<dyn Write>>>,
+    collisions: Option<csv::Writer<Box<dyn Write>>>,
+}
+
+impl Exporter {
+    pub fn new() -> Self {
+        Exporter {
+            frame: Frame::Inertial,
+            csv: None,
+            jsonl: None,
+            diagnostics: None,
+            collisions: None,
+        }
+    }
+
+    /// Writes body states in `frame` rather than the simulation frame. Diagnostics and
+    /// collisions stay in the simulation frame.
+    pub fn with_frame(mut self, frame: Frame) -> Self {
+        self.frame = frame;
+        self
+    }
+
+    /// Writes CSV with a header row to `writer`.
+    pub fn with_csv(mut self, writer: Box<dyn Write>) -> Self {
+        self.csv = Some(csv::Writer::from_writer(writer));
+        self
+    }
+
+    /// Writes one JSON object per line to `writer`.
+    pub fn with_jsonl(mut self, writer: Box<dyn Write>) -> Self {
+        self.jsonl = Some(writer);
+        self
+    }
+
+    /// Writes diagnostics as CSV with a header row to `writer`.
+    pub fn with_diagnostics(mut self, writer: Box<dyn Write>) -> Self {
+        self.diagnostics = Some(csv::Writer::from_writer(writer));
+        self
+    }
+
+    /// Writes collisions as CSV with a header row to `writer`.
+    pub fn with_collisions(mut self, writer: Box<dyn Write>) -> Self {
+        self.collisions = Some(csv::Writer::from_writer(writer));
+        self
+    }
+
+    /// Whether [`Exporter::record_diagnostics`] writes anything, as measuring them costs O(N²).
+    pub fn wants_diagnostics(&self) -> bool {
+        self.diagnostics.is_some()
+    }
+
+    /// Records the state of every body at `time`.
+    pub fn record<F: Float, const D: usize>(
+        &mut self,
+        time: F,
+        bodies: &BodySet<F, D>,
+    ) -> Result<(), Box<dyn Error>> {
+        let transform = self.frame.resolve(bodies);
+        for body in bodies.iter() {
+            let state = BodyState::new(time, &body, &transform);
+            if let Some(csv) = self.csv.as_mut() {
+                csv.serialize(&state)?;
+            }
+            if let Some(jsonl) = self.jsonl.as_mut() {
+                serde_json::to_writer(&mut *jsonl, &state)?;
+                jsonl.write_all(b"\n")?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Records the conserved quantities and their drift at `time`.
+    pub fn record_diagnostics<F: Float, const D: usize>(
+        &mut self,
+        time: F,
+        diagnostics: &Diagnostics<F, D>,
+        drift: &Drift,
+    ) -> Result<(), Box<dyn Error>> {
+        if let Some(csv) = self.diagnostics.as_mut() {
+            csv.serialize(DiagnosticsState::new(time, diagnostics, drift))?;
+        }
+        Ok(())
+    }
+
+    /// Records `collisions`, e.g. the ones that happened during the last step.
+    ///
+    /// The frame follows the bodies removed by them, so every collision must be recorded before
+    /// the next [`Exporter::record`].
+    pub fn record_collisions<F: Float, const D: usize>(
+        &mut self,
+        collisions: &[Collision<F, D>],
+    ) -> Result<(), Box<dyn Error>> {
+        let mut removed: Vec<usize> = collisions.iter().filter_map(|c| c.removed).collect();
+        removed.sort_unstable();
+        for index in removed.into_iter().rev() {
+            self.frame.remove(index);
+        }
+        if let Some(csv) = self.collisions.as_mut() {
+            for collision in collisions {
+                csv.serialize(CollisionState::new(collision))?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> Result<(), Box<dyn Error>> {
+        if let Some(csv) = self.csv.as_mut() {
+            csv.flush()?;
+        }
+        if let Some(csv) = self.diagnostics.as_mut() {
+            csv.flush()?;
+        }
+        if let Some(csv) = self.collisions.as_mut() {
+            csv.flush()?;
+        }
+        if let Some(jsonl) = self.jsonl.as_mut() {
+            jsonl.flush()?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for Exporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
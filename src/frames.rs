@@ -0,0 +1,359 @@
+use crate::bodies::BodySet;
+use crate::quantities::spatial::{Cartesian, Velocity};
+use crate::quantities::{Float, Tensor};
+use crate::units::length::meter::Meter;
+use nalgebra::SVector;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// A reference frame to express positions and velocities in, e.g. for the exporter or the
+/// viewer. The simulation itself always runs in its own inertial frame.
+///
+/// Parsed from `inertial`, `barycentric`, `body:<index>` (`heliocentric` for the first body)
+/// or `rotating:<primary>,<secondary>`.
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Frame {
+    /// The frame the simulation runs in.
+    #[default]
+    Inertial,
+    /// Inertial frame centred on, and moving with, the centre of mass of every body.
+    Barycentric,
+    /// Non-rotating frame centred on, and moving with, the body with this index.
+    Body(usize),
+    /// Synodic frame of two bodies: centred on their centre of mass and rotating about the z
+    /// axis so that `secondary` stays on the positive x axis from `primary`.
+    ///
+    /// The rotation follows the pair at every instant, so for a circular pair (e.g. the Sun and
+    /// Jupiter) this is the uniformly rotating frame of the restricted three-body problem, in
+    /// which the Lagrange points are fixed.
+    Rotating { primary: usize, secondary: usize },
+}
+
+impl Frame {
+    /// Every body index the frame refers to.
+    fn indices(&self) -> Vec<usize> {
+        match *self {
+            Frame::Inertial | Frame::Barycentric => vec![],
+            Frame::Body(index) => vec![index],
+            Frame::Rotating { primary, secondary } => vec![primary, secondary],
+        }
+    }
+
+    /// Checks that the frame can be resolved on `count` bodies.
+    pub fn validate(&self, count: usize) -> Result<(), FrameError> {
+        if let Some(&index) = self.indices().iter().find(|&&index| index >= count) {
+            return Err(FrameError::NoBody { index, count });
+        }
+        match *self {
+            Frame::Rotating { primary, secondary } if primary == secondary => {
+                Err(FrameError::SamePair(primary))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Keeps referring to the same bodies after body `index` is taken out of the simulation,
+    /// e.g. by a collision. Falls back to the barycentric frame if it was one of them.
+    pub fn remove(&mut self, index: usize) {
+        let shift = |body: &mut usize| match (*body).cmp(&index) {
+            std::cmp::Ordering::Equal => false,
+            std::cmp::Ordering::Greater => {
+                *body -= 1;
+                true
+            }
+            std::cmp::Ordering::Less => true,
+        };
+        let kept = match self {
+            Frame::Inertial | Frame::Barycentric => true,
+            Frame::Body(body) => shift(body),
+            Frame::Rotating { primary, secondary } => shift(primary) & shift(secondary),
+        };
+        if !kept {
+            *self = Frame::Barycentric;
+        }
+    }
+
+    /// Where the frame is and how it moves given the current state of `bodies`.
+    ///
+    /// # Panics
+    ///
+    /// If the frame refers to a body not in `bodies`, see [`Frame::validate`].
+    pub fn resolve<F: Float, const D: usize>(&self, bodies: &BodySet<F, D>) -> Transform<F, D> {
+        match *self {
+            Frame::Inertial => Transform::identity(),
+            Frame::Barycentric => Transform {
+                origin: *bodies.barycentre().covariant(),
+                velocity: *bodies.barycentre_velocity().covariant(),
+                ..Transform::identity()
+            },
+            Frame::Body(index) => {
+                let body = bodies.get(index);
+                Transform {
+                    origin: *body.pos().covariant(),
+                    velocity: *body.velocity().covariant(),
+                    ..Transform::identity()
+                }
+            }
+            Frame::Rotating { primary, secondary } => {
+                let (primary, secondary) = (bodies.get(primary), bodies.get(secondary));
+                let total = primary.mass() + secondary.mass();
+                // Massless pairs rotate about their midpoint
+                let (weight, other) = if total > F::zero() {
+                    (primary.mass() / total, secondary.mass() / total)
+                } else {
+                    (F::cast_from(0.5), F::cast_from(0.5))
+                };
+                let (p, s) = (*primary.pos().covariant(), *secondary.pos().covariant());
+                let (v, w) = (
+                    *primary.velocity().covariant(),
+                    *secondary.velocity().covariant(),
+                );
+                let (separation, relative) = (s - p, w - v);
+                let distance_squared =
+                    separation[0] * separation[0] + separation[1] * separation[1];
+                let rate = if distance_squared > F::zero() {
+                    (separation[0] * relative[1] - separation[1] * relative[0]) / distance_squared
+                } else {
+                    F::zero()
+                };
+                Transform {
+                    origin: p * weight + s * other,
+                    velocity: v * weight + w * other,
+                    angle: separation[1].atan2(separation[0]),
+                    rate,
+                }
+            }
+        }
+    }
+}
+
+impl Display for Frame {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Frame::Inertial => write!(f, "inertial"),
+            Frame::Barycentric => write!(f, "barycentric"),
+            Frame::Body(index) => write!(f, "body:{index}"),
+            Frame::Rotating { primary, secondary } => write!(f, "rotating:{primary},{secondary}"),
+        }
+    }
+}
+
+impl FromStr for Frame {
+    type Err = FrameError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || FrameError::Parse(s.to_string());
+        let index = |s: &str| s.trim().parse::<usize>().map_err(|_| invalid());
+        let (name, bodies) = s.split_once(':').unwrap_or((s, ""));
+        match (name.trim(), bodies) {
+            ("inertial", "") => Ok(Frame::Inertial),
+            ("barycentric", "") => Ok(Frame::Barycentric),
+            ("heliocentric", "") => Ok(Frame::Body(0)),
+            ("body", body) => Ok(Frame::Body(index(body)?)),
+            ("rotating", pair) => {
+                let (primary, secondary) = pair.split_once(',').ok_or_else(invalid)?;
+                Ok(Frame::Rotating {
+                    primary: index(primary)?,
+                    secondary: index(secondary)?,
+                })
+            }
+            _ => Err(invalid()),
+        }
+    }
+}
+
+/// Position, velocity and rotation of a [`Frame`] at one instant, relative to the simulation
+/// frame.
+///
+/// Rotations are about the z axis, i.e. in the plane of the first two components.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Transform<F, const D: usize> {
+    origin: SVector<F, D>,
+    velocity: SVector<F, D>,
+    /// Angle of the frame's x axis from the simulation's, in radians.
+    angle: F,
+    /// Angular velocity of the frame, in radians per second.
+    rate: F,
+}
+
+impl<F: Float, const D: usize> Transform<F, D> {
+    pub fn identity() -> Self {
+        Transform {
+            origin: SVector::zeros(),
+            velocity: SVector::zeros(),
+            angle: F::zero(),
+            rate: F::zero(),
+        }
+    }
+
+    /// Rotates `vector` by minus the frame angle, into the frame's axes.
+    fn rotate(&self, mut vector: SVector<F, D>) -> SVector<F, D> {
+        if self.angle != F::zero() {
+            let (sin, cos) = self.angle.sin_cos();
+            let (x, y) = (vector[0], vector[1]);
+            vector[0] = cos * x + sin * y;
+            vector[1] = cos * y - sin * x;
+        }
+        vector
+    }
+
+    /// `pos`, from the simulation frame, in this frame.
+    pub fn position(&self, pos: &Cartesian<F, D, Meter>) -> Cartesian<F, D, Meter> {
+        Cartesian::from_vector(self.rotate(pos.covariant() - self.origin))
+    }
+
+    /// Velocity of a body at `pos` moving at `velocity`, both from the simulation frame, in this
+    /// frame. A rotating frame adds the apparent velocity `-ω × r` of its rotation.
+    pub fn velocity(
+        &self,
+        pos: &Cartesian<F, D, Meter>,
+        velocity: &Velocity<Cartesian<F, D, Meter>, D, 1>,
+    ) -> Velocity<Cartesian<F, D, Meter>, D, 1> {
+        let mut relative = velocity.covariant() - self.velocity;
+        if self.rate != F::zero() {
+            let offset = pos.covariant() - self.origin;
+            relative[0] += self.rate * offset[1];
+            relative[1] -= self.rate * offset[0];
+        }
+        Velocity::from_vector(self.rotate(relative))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FrameError {
+    Parse(String),
+    NoBody { index: usize, count: usize },
+    SamePair(usize),
+}
+
+impl Display for FrameError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FrameError::Parse(frame) => write!(
+                f,
+                "Invalid frame `{frame}`: expected inertial, barycentric, heliocentric, \
+                 body:<index> or rotating:<primary>,<secondary>"
+            ),
+            FrameError::NoBody { index, count } => write!(
+                f,
+                "Invalid frame: no body {index} (there are {count}, counting from 0)"
+            ),
+            FrameError::SamePair(index) => {
+                write!(f, "Invalid frame: body {index} cannot rotate about itself")
+            }
+        }
+    }
+}
+
+impl Error for FrameError {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::bodies::AstroBody;
+
+    /// A circular pair of masses 3 and 1, a distance 4 apart, at `phase` radians along the
+    /// orbit, drifting as a whole at (5, 0).
+    fn pair(phase: f64) -> BodySet<f64, 2> {
+        let (grav, distance): (f64, f64) = (1., 4.);
+        let rate = (grav * 4. / (distance * distance * distance)).sqrt();
+        let direction = SVector::<f64, 2>::new(phase.cos(), phase.sin());
+        let along = SVector::<f64, 2>::new(-phase.sin(), phase.cos());
+        let drift = SVector::<f64, 2>::new(5., 0.);
+        let body = |mass: f64, radius: f64| {
+            AstroBody::new_dynamic(
+                mass,
+                Cartesian::from_vector(direction * radius + drift * phase),
+                Velocity::from_vector(along * radius * rate + drift * rate),
+            )
+        };
+        // Bodies sit at distances 1 and 3 from the barycentre, on either side of it
+        BodySet::from(vec![body(3., -1.), body(1., 3.)])
+    }
+
+    /// Ensures that a circular pair stands still in its rotating frame, the secondary on the
+    /// positive x axis.
+    #[test]
+    fn test_rotating_frame_follows_circular_pair() {
+        let frame = Frame::Rotating {
+            primary: 0,
+            secondary: 1,
+        };
+        for phase in [0., 0.4, 2., 5.] {
+            let bodies = pair(phase);
+            let transform = frame.resolve(&bodies);
+            for (body, x) in bodies.iter().zip([-1., 3.]) {
+                let pos = transform.position(&body.pos());
+                let velocity = transform.velocity(&body.pos(), &body.velocity());
+                assert!((pos.covariant() - SVector::<f64, 2>::new(x, 0.)).norm() < 1e-12);
+                assert!(
+                    velocity.covariant().norm() < 1e-12,
+                    "{velocity:?} at {phase}"
+                );
+            }
+        }
+    }
+
+    /// Ensures that the barycentric frame has no net momentum, and a body-centred frame puts its
+    /// body at rest at the origin.
+    #[test]
+    fn test_translating_frames() {
+        let bodies = pair(1.);
+        let transform = Frame::Barycentric.resolve(&bodies);
+        let momentum = bodies.iter().fold(SVector::<f64, 2>::zeros(), |sum, body| {
+            sum + transform
+                .velocity(&body.pos(), &body.velocity())
+                .covariant()
+                * body.mass()
+        });
+        assert!(momentum.norm() < 1e-12);
+
+        let transform = Frame::Body(1).resolve(&bodies);
+        let body = bodies.get(1);
+        assert_eq!(transform.position(&body.pos()), Cartesian::zero());
+        assert_eq!(
+            transform
+                .velocity(&body.pos(), &body.velocity())
+                .covariant(),
+            Velocity::<Cartesian<f64, 2, Meter>, 2, 1>::zero().covariant()
+        );
+    }
+
+    /// Ensures that frames parse back from how they are displayed, and adjust to removals.
+    #[test]
+    fn test_parse_and_remove() {
+        for frame in [
+            Frame::Inertial,
+            Frame::Barycentric,
+            Frame::Body(3),
+            Frame::Rotating {
+                primary: 0,
+                secondary: 5,
+            },
+        ] {
+            assert_eq!(frame.to_string().parse(), Ok(frame));
+        }
+        assert_eq!("heliocentric".parse(), Ok(Frame::Body(0)));
+        assert!("rotating:1".parse::<Frame>().is_err());
+        assert_eq!(
+            Frame::Body(4).validate(4),
+            Err(FrameError::NoBody { index: 4, count: 4 })
+        );
+
+        let mut frame = Frame::Rotating {
+            primary: 0,
+            secondary: 5,
+        };
+        frame.remove(2);
+        assert_eq!(
+            frame,
+            Frame::Rotating {
+                primary: 0,
+                secondary: 4
+            }
+        );
+        frame.remove(0);
+        assert_eq!(frame, Frame::Barycentric);
+    }
+}
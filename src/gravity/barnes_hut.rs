@@ -0,0 +1,303 @@
+use crate::quantities::Float;
+use nalgebra::SVector;
+
+/// Depth below which cells are no longer split, so coincident bodies share a leaf.
+const MAX_DEPTH: usize = 32;
+
+/// A cell of the tree, covering the cube `centre ± half_width` in every dimension.
+#[derive(Debug, Clone)]
+struct Node<F, const D: usize> {
+    centre: SVector<F, D>,
+    half_width: F,
+    mass: F,
+    /// Mass-weighted sum of positions while building, centre of mass once built.
+    centre_of_mass: SVector<F, D>,
+    /// Index of the first of the `2^D` consecutive children, if the cell is split.
+    first_child: Option<usize>,
+    /// Bodies held by a leaf cell.
+    bodies: Vec<usize>,
+}
+
+impl<F: Float, const D: usize> Node<F, D> {
+    fn new(centre: SVector<F, D>, half_width: F) -> Self {
+        Node {
+            centre,
+            half_width,
+            mass: F::zero(),
+            centre_of_mass: SVector::zeros(),
+            first_child: None,
+            bodies: Vec::new(),
+        }
+    }
+
+    /// Index (among its `2^D` siblings) of the child cell containing `pos`.
+    fn child_of(&self, pos: &SVector<F, D>) -> usize {
+        (0..D)
+            .filter(|&k| pos[k] >= self.centre[k])
+            .fold(0, |index, k| index | 1 << k)
+    }
+
+    fn contains(&self, pos: &SVector<F, D>) -> bool {
+        (pos - self.centre).amax() <= self.half_width
+    }
+}
+
+/// Barnes–Hut tree over `D`-dimensional positions (a quadtree for `D = 2`, an octree for
+/// `D = 3`).
+///
+/// Accelerations are returned per unit gravitational constant, i.e. they must still be scaled by
+/// `G`.
+#[derive(Debug, Clone)]
+pub struct Tree<'a, F, const D: usize> {
+    nodes: Vec<Node<F, D>>,
+    positions: &'a [SVector<F, D>],
+    masses: &'a [F],
+}
+
+impl<'a, F: Float, const D: usize> Tree<'a, F, D> {
+    pub fn new(positions: &'a [SVector<F, D>], masses: &'a [F]) -> Self {
+        Self::with_bodies(positions, masses, 0..positions.len())
+    }
+
+    /// Tree holding only `bodies`, indices into `positions` and `masses`, e.g. the massive ones.
+    pub fn with_bodies(
+        positions: &'a [SVector<F, D>],
+        masses: &'a [F],
+        bodies: impl Iterator<Item = usize> + Clone,
+    ) -> Self {
+        let (min, max) = bodies.clone().map(|i| &positions[i]).fold(
+            (
+                SVector::<F, D>::repeat(F::cast_from(f64::INFINITY)),
+                SVector::<F, D>::repeat(F::cast_from(f64::NEG_INFINITY)),
+            ),
+            |(min, max), pos| (min.inf(pos), max.sup(pos)),
+        );
+        let centre = (min + max) / F::cast_from(2.);
+        // Slightly enlarged so that bodies on the boundary are strictly inside the root.
+        let half_width = ((max - min).amax() / F::cast_from(2.))
+            .max(F::cast_from(f32::MIN_POSITIVE as f64))
+            * F::cast_from(1.001);
+
+        let mut tree = Tree {
+            nodes: vec![Node::new(centre, half_width)],
+            positions,
+            masses,
+        };
+        let mut bodies = bodies.peekable();
+        if bodies.peek().is_none() {
+            return tree;
+        }
+        for i in bodies {
+            tree.insert(i);
+        }
+        for node in tree.nodes.iter_mut() {
+            if node.mass > F::zero() {
+                node.centre_of_mass /= node.mass;
+            } else {
+                node.centre_of_mass = node.centre;
+            }
+        }
+        tree
+    }
+
+    fn accumulate(&mut self, node: usize, body: usize) {
+        let mass = self.masses[body];
+        self.nodes[node].mass += mass;
+        self.nodes[node].centre_of_mass += self.positions[body] * mass;
+    }
+
+    fn insert(&mut self, body: usize) {
+        let pos = self.positions[body];
+        let (mut node, mut depth) = (0, 0);
+        loop {
+            self.accumulate(node, body);
+            if self.nodes[node].first_child.is_none() {
+                if self.nodes[node].bodies.is_empty() || depth >= MAX_DEPTH {
+                    self.nodes[node].bodies.push(body);
+                    return;
+                }
+                self.subdivide(node);
+            }
+            node = self.nodes[node].first_child.unwrap() + self.nodes[node].child_of(&pos);
+            depth += 1;
+        }
+    }
+
+    /// Splits a leaf into `2^D` children and moves its bodies into them.
+    fn subdivide(&mut self, node: usize) {
+        let first = self.nodes.len();
+        let (centre, half_width) = (
+            self.nodes[node].centre,
+            self.nodes[node].half_width / F::cast_from(2.),
+        );
+        for index in 0..1 << D {
+            let offset = SVector::<F, D>::from_fn(|k, _| {
+                if index & 1 << k == 0 {
+                    -half_width
+                } else {
+                    half_width
+                }
+            });
+            self.nodes.push(Node::new(centre + offset, half_width));
+        }
+        self.nodes[node].first_child = Some(first);
+        for body in std::mem::take(&mut self.nodes[node].bodies) {
+            let child = first + self.nodes[node].child_of(&self.positions[body]);
+            self.accumulate(child, body);
+            self.nodes[child].bodies.push(body);
+        }
+    }
+
+    /// Acceleration of body `index` due to all the others, per unit gravitational constant,
+    /// whether or not it is held by the tree.
+    ///
+    /// Cells not containing the body are approximated by their centre of mass when their width
+    /// is below `opening_angle` times their distance. Forces are softened by `softening`, see
+    /// [`Gravity::softening`](crate::gravity::Gravity::softening).
+    pub fn acceleration(&self, index: usize, opening_angle: F, softening: F) -> SVector<F, D> {
+        self.acceleration_at(
+            &self.positions[index],
+            Some(index),
+            opening_angle,
+            softening,
+        )
+    }
+
+    /// Acceleration at `pos` due to all the bodies but `skip`, per unit gravitational constant,
+    /// see [`Tree::acceleration`].
+    pub fn acceleration_at(
+        &self,
+        pos: &SVector<F, D>,
+        skip: Option<usize>,
+        opening_angle: F,
+        softening: F,
+    ) -> SVector<F, D> {
+        let mut acceleration = SVector::<F, D>::zeros();
+        self.visit(0, pos, skip, opening_angle, softening, &mut acceleration);
+        acceleration
+    }
+
+    /// Adds the pull of cell `n` at `pos` to `acceleration`, opening it as needed.
+    ///
+    /// Recursing rather than keeping a stack of cells to visit leaves the force evaluation
+    /// without allocations: the depth is bounded by [`MAX_DEPTH`].
+    fn visit(
+        &self,
+        n: usize,
+        pos: &SVector<F, D>,
+        skip: Option<usize>,
+        opening_angle: F,
+        softening: F,
+        acceleration: &mut SVector<F, D>,
+    ) {
+        let node = &self.nodes[n];
+        if node.mass == F::zero() {
+            return;
+        }
+        match node.first_child {
+            None => {
+                for &other in node.bodies.iter().filter(|&&other| Some(other) != skip) {
+                    *acceleration +=
+                        pull(pos, &self.positions[other], self.masses[other], softening);
+                }
+            }
+            Some(first) => {
+                let distance = (node.centre_of_mass - pos).norm();
+                if !node.contains(pos)
+                    && F::cast_from(2.) * node.half_width < opening_angle * distance
+                {
+                    *acceleration += pull(pos, &node.centre_of_mass, node.mass, softening);
+                } else {
+                    for child in first..first + (1 << D) {
+                        self.visit(child, pos, skip, opening_angle, softening, acceleration);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Acceleration at `pos` towards a point `mass` at `other`, per unit gravitational constant.
+pub(crate) fn pull<F: Float, const D: usize>(
+    pos: &SVector<F, D>,
+    other: &SVector<F, D>,
+    mass: F,
+    softening: F,
+) -> SVector<F, D> {
+    let displacement = other - pos;
+    if displacement.norm_squared() == F::zero() {
+        return SVector::zeros();
+    }
+    let distance_squared = displacement.norm_squared() + softening * softening;
+    displacement * (mass / (distance_squared * distance_squared.sqrt()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::bodies::{AstroBody, BodySet};
+    use crate::gravity::{Gravity, Solver};
+    use crate::quantities::spatial::Cartesian;
+    use crate::quantities::Tensor;
+
+    /// Deterministic uniform random numbers in [0, 1) (xorshift), to avoid a test dependency.
+    fn uniform(state: &mut u32) -> f32 {
+        *state ^= *state << 13;
+        *state ^= *state >> 17;
+        *state ^= *state << 5;
+        (*state >> 8) as f32 / (1 << 24) as f32
+    }
+
+    /// Ensures that the Barnes–Hut forces on a 10k-body cluster agree with the direct sum:
+    /// every sampled body within 5% and all of them within 2% RMS for an opening angle of 0.5.
+    #[test]
+    fn test_matches_direct_sum() {
+        let mut state = 2463534242;
+        let bodies: Vec<_> = (0..10_000)
+            .map(|_| {
+                // Centrally concentrated disc of radius 1
+                let radius = uniform(&mut state).powi(2);
+                let angle = uniform(&mut state) * std::f32::consts::TAU;
+                AstroBody::new_static(
+                    1. + uniform(&mut state),
+                    Cartesian::new(radius * angle.cos(), radius * angle.sin()),
+                )
+            })
+            .collect();
+        let mut direct = bodies.clone();
+        let mut tree = BodySet::from(bodies.clone());
+        Gravity::new(1.)
+            .with_solver(Solver::BarnesHut { opening_angle: 0.5 })
+            .gravitate_all(&mut tree);
+
+        let mut squared_errors = 0.;
+        let samples: Vec<usize> = (0..bodies.len()).step_by(100).collect();
+        for &i in samples.iter() {
+            let exact = direct[i].gravitate(&bodies, &1.);
+            let error = (*tree.get(i).force().covariant() - *exact.covariant()).norm()
+                / exact.covariant().norm();
+            assert!(error < 0.05, "body {i} has relative force error {error}");
+            squared_errors += error * error;
+        }
+        let rms = (squared_errors / samples.len() as f32).sqrt();
+        assert!(rms < 0.02, "RMS relative force error {rms}");
+    }
+
+    /// Ensures that an opening angle of 0 reduces to the direct sum.
+    #[test]
+    fn test_zero_opening_angle_is_exact() {
+        let positions: Vec<SVector<f32, 2>> = (0..50)
+            .map(|i| SVector::<f32, 2>::new((i as f32 * 0.7).sin(), (i as f32 * 1.3).cos()))
+            .collect();
+        let masses = vec![1.; positions.len()];
+        let tree = Tree::new(&positions, &masses);
+        for i in 0..positions.len() {
+            let exact = (0..positions.len())
+                .filter(|&j| j != i)
+                .fold(SVector::<f32, 2>::zeros(), |a, j| {
+                    a + pull(&positions[i], &positions[j], masses[j], 0.)
+                });
+            assert!((tree.acceleration(i, 0., 0.) - exact).norm() <= 1e-4 * exact.norm());
+        }
+    }
+}
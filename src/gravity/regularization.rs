@@ -0,0 +1,304 @@
+use crate::bodies::BodySet;
+use crate::quantities::Float;
+use nalgebra::{Matrix4, SVector, Vector3, Vector4};
+
+/// Iterations used to find the fictitious time spanning a step.
+const ITERATIONS: usize = 100;
+
+/// A close pair of bodies whose relative motion is integrated as a Kepler orbit in
+/// Kustaanheimo–Stiefel (KS) coordinates instead of by the integrator.
+///
+/// KS coordinates turn the two-body problem into a harmonic oscillator in 4D with a fictitious
+/// time `dτ = dt / r`, which has no singularity at `r = 0` and is solved exactly, so a pair stays
+/// accurate however close it gets. The rest of the system, including the tides on the pair, is
+/// left to the integrator with the mutual attraction of the pair switched off (see
+/// [`Gravity::regularized`](crate::gravity::Gravity::regularized)); the change it makes to the
+/// relative motion is added to the Kepler orbit as a perturbation.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Encounter<F: Float, const D: usize> {
+    pub first: usize,
+    pub second: usize,
+    /// Position and velocity of `second` relative to `first` at the start of the step.
+    pos: SVector<F, D>,
+    velocity: SVector<F, D>,
+}
+
+impl<F: Float, const D: usize> Encounter<F, D> {
+    /// Pairs of bodies closer than `radius`, closest first, each body in at most one pair.
+    pub fn find(bodies: &BodySet<F, D>, radius: F) -> Vec<Self> {
+        let mut candidates = vec![];
+        for i in 0..bodies.len() {
+            for j in i + 1..bodies.len() {
+                let distance = (bodies.pos[j] - bodies.pos[i]).norm();
+                // Two test particles do not attract each other in the first place
+                if distance < radius && (bodies.is_massive(i) || bodies.is_massive(j)) {
+                    candidates.push((distance, i, j));
+                }
+            }
+        }
+        candidates.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+
+        let mut paired = vec![false; bodies.len()];
+        let mut encounters = vec![];
+        for (_, i, j) in candidates {
+            if !paired[i] && !paired[j] {
+                paired[i] = true;
+                paired[j] = true;
+                encounters.push(Encounter {
+                    first: i,
+                    second: j,
+                    pos: bodies.pos[j] - bodies.pos[i],
+                    velocity: bodies.velocity[j] - bodies.velocity[i],
+                });
+            }
+        }
+        encounters
+    }
+
+    /// Puts the pair back on its Kepler orbit after the integrator has advanced `bodies` by
+    /// `step_time` without their mutual attraction.
+    ///
+    /// The centre of mass of the pair is kept where the integrator moved it.
+    pub fn finish(&self, bodies: &mut BodySet<F, D>, grav: F, step_time: F) {
+        let (i, j) = (self.first, self.second);
+        let (first_mass, second_mass) = (bodies.mass[i], bodies.mass[j]);
+        let total = first_mass + second_mass;
+        if total <= F::zero() {
+            return;
+        }
+        let pos = bodies.pos[j] - bodies.pos[i];
+        let velocity = bodies.velocity[j] - bodies.velocity[i];
+        // Whatever the integrator did besides moving in a straight line is the perturbation
+        let perturbation = (
+            pos - self.pos - self.velocity * step_time,
+            velocity - self.velocity,
+        );
+        let (kepler_pos, kepler_velocity) = kepler(
+            &embed(&self.pos),
+            &embed(&self.velocity),
+            (grav * total).as_f64(),
+            step_time.as_f64(),
+        );
+        let pos = project::<F, D>(&kepler_pos) + perturbation.0;
+        let velocity = project::<F, D>(&kepler_velocity) + perturbation.1;
+
+        let centre = (bodies.pos[i] * first_mass + bodies.pos[j] * second_mass) / total;
+        let centre_velocity =
+            (bodies.velocity[i] * first_mass + bodies.velocity[j] * second_mass) / total;
+        let (first_share, second_share) = (second_mass / total, first_mass / total);
+        bodies.pos[i] = centre - pos * first_share;
+        bodies.pos[j] = centre + pos * second_share;
+        bodies.velocity[i] = centre_velocity - velocity * first_share;
+        bodies.velocity[j] = centre_velocity + velocity * second_share;
+    }
+}
+
+/// Advances the relative position `pos` and velocity `velocity` of a pair with gravitational
+/// parameter `mu` along their Kepler orbit by `step_time`, in KS coordinates.
+pub fn kepler(
+    pos: &Vector3<f64>,
+    velocity: &Vector3<f64>,
+    mu: f64,
+    step_time: f64,
+) -> (Vector3<f64>, Vector3<f64>) {
+    let radius = pos.norm();
+    if radius == 0. || mu <= 0. {
+        return (pos + velocity * step_time, *velocity);
+    }
+    let u = to_ks(pos);
+    let du = ks_matrix(&u).transpose() * Vector4::new(velocity.x, velocity.y, velocity.z, 0.) / 2.;
+    // u'' = (h / 2) u, with h the orbital energy per unit reduced mass
+    let oscillator = Oscillator {
+        k: (velocity.norm_squared() / 2. - mu / radius) / 2.,
+        a: u.norm_squared(),
+        b: du.norm_squared(),
+        c: u.dot(&du),
+    };
+    let (c, s) = oscillator.basis(oscillator.fictitious_time(step_time));
+    let (u, du) = (u * c + du * s, u * (oscillator.k * s) + du * c);
+    let matrix = ks_matrix(&u);
+    let pos = matrix * u;
+    let velocity = matrix * du * (2. / u.norm_squared());
+    (pos.xyz(), velocity.xyz())
+}
+
+/// KS coordinates of `pos`, picking the branch that avoids dividing by a small number.
+fn to_ks(pos: &Vector3<f64>) -> Vector4<f64> {
+    let radius = pos.norm();
+    if pos.x >= 0. {
+        let u1 = ((radius + pos.x) / 2.).sqrt();
+        Vector4::new(u1, pos.y / (2. * u1), pos.z / (2. * u1), 0.)
+    } else {
+        let u2 = ((radius - pos.x) / 2.).sqrt();
+        Vector4::new(pos.y / (2. * u2), u2, 0., pos.z / (2. * u2))
+    }
+}
+
+/// The KS matrix `L(u)`, with `(x, y, z, 0) = L(u) u`.
+fn ks_matrix(u: &Vector4<f64>) -> Matrix4<f64> {
+    Matrix4::new(
+        u[0], -u[1], -u[2], u[3], //
+        u[1], u[0], -u[3], -u[2], //
+        u[2], u[3], u[0], u[1], //
+        u[3], -u[2], u[1], -u[0],
+    )
+}
+
+/// Solution `u(τ) = u₀ c(τ) + u₀' s(τ)` of `u'' = k u`, with `a = |u₀|²`, `b = |u₀'|²` and
+/// `c = u₀ · u₀'`.
+struct Oscillator {
+    k: f64,
+    a: f64,
+    b: f64,
+    c: f64,
+}
+
+impl Oscillator {
+    /// `c(τ)` and `s(τ)`, with `c(0) = 1`, `s(0) = 0` and `s' = c`.
+    fn basis(&self, tau: f64) -> (f64, f64) {
+        let w = self.k.abs().sqrt();
+        if self.k < 0. {
+            ((w * tau).cos(), (w * tau).sin() / w)
+        } else if self.k > 0. {
+            ((w * tau).cosh(), (w * tau).sinh() / w)
+        } else {
+            (1., tau)
+        }
+    }
+
+    /// Distance `r = |u|²` at `τ`, i.e. `dt / dτ`.
+    fn radius(&self, tau: f64) -> f64 {
+        let (c, s) = self.basis(tau);
+        self.a * c * c + self.b * s * s + 2. * self.c * c * s
+    }
+
+    /// Physical time elapsed at `τ`, the integral of [`Oscillator::radius`].
+    fn time(&self, tau: f64) -> f64 {
+        let w = self.k.abs().sqrt();
+        let (cc, ss, cs) = if self.k < 0. {
+            let half = (2. * w * tau).sin() / (4. * w);
+            (
+                tau / 2. + half,
+                (tau / 2. - half) / (w * w),
+                (w * tau).sin().powi(2) / (2. * w * w),
+            )
+        } else if self.k > 0. {
+            let half = (2. * w * tau).sinh() / (4. * w);
+            (
+                tau / 2. + half,
+                (half - tau / 2.) / (w * w),
+                (w * tau).sinh().powi(2) / (2. * w * w),
+            )
+        } else {
+            (tau, tau.powi(3) / 3., tau.powi(2) / 2.)
+        };
+        self.a * cc + self.b * ss + 2. * self.c * cs
+    }
+
+    /// Fictitious time at which `step_time` has elapsed, by Newton's method kept within a
+    /// bracket, as the time is monotonic in `τ`.
+    fn fictitious_time(&self, step_time: f64) -> f64 {
+        if step_time == 0. {
+            return 0.;
+        }
+        let direction = step_time.signum();
+        let mut tau = step_time / self.a;
+        let (mut low, mut high) = (0., tau);
+        for _ in 0..ITERATIONS {
+            if (self.time(high) - step_time) * direction >= 0. {
+                break;
+            }
+            low = high;
+            high *= 2.;
+        }
+        for _ in 0..ITERATIONS {
+            let error = self.time(tau) - step_time;
+            if error.abs() <= 1e-14 * step_time.abs() {
+                break;
+            }
+            if error * direction > 0. {
+                high = tau;
+            } else {
+                low = tau;
+            }
+            tau -= error / self.radius(tau);
+            if (tau - low) * (tau - high) > 0. {
+                tau = (low + high) / 2.;
+            }
+        }
+        tau
+    }
+}
+
+/// `vector` as a 3D `f64` vector, padded with zeros.
+fn embed<F: Float, const D: usize>(vector: &SVector<F, D>) -> Vector3<f64> {
+    Vector3::from_fn(|k, _| vector.get(k).map_or(0., |x| x.as_f64()))
+}
+
+/// The first `D` components of `vector`.
+fn project<F: Float, const D: usize>(vector: &Vector3<f64>) -> SVector<F, D> {
+    SVector::from_fn(|k, _| F::cast_from(vector[k]))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::bodies::AstroBody;
+    use crate::integrator::Scheme;
+    use crate::orbit::Elements;
+    use crate::quantities::spatial::{Cartesian, Velocity};
+    use crate::quantities::Tensor;
+    use crate::units::measure::Measure;
+    use crate::Simulation;
+
+    /// Ensures that the KS solution follows the orbit given by the Keplerian elements.
+    #[test]
+    fn test_kepler_matches_elements() {
+        let periapsis = Elements {
+            semi_major_axis: Measure::Bare(1.),
+            eccentricity: 0.9,
+            argument_of_periapsis: 30.,
+            inclination: 40.,
+            longitude_of_ascending_node: 70.,
+            ..Default::default()
+        };
+        let (pos, velocity) = periapsis.state_vector::<f64, 3>(1.);
+        let (pos, velocity) = (*pos.covariant(), *velocity.covariant());
+        // With a = mu = 1 the mean motion is 1: a mean anomaly of 1 rad is reached at t = 1
+        for (time, mean_anomaly) in [(1., 1f64), (-2., -2.), (std::f64::consts::TAU, 0.)] {
+            let (expected_pos, expected_velocity) = Elements {
+                mean_anomaly: Some(mean_anomaly.to_degrees()),
+                ..periapsis
+            }
+            .state_vector::<f64, 3>(1.);
+            let (pos, velocity) = kepler(&pos, &velocity, 1., time);
+            assert!((pos - expected_pos.covariant()).norm() < 1e-9);
+            assert!((velocity - expected_velocity.covariant()).norm() < 1e-9);
+        }
+    }
+
+    /// Ensures that a regularized binary keeps its energy with steps far too long for leapfrog.
+    #[test]
+    fn test_regularized_binary() {
+        let elements = Elements {
+            semi_major_axis: Measure::Bare(1.),
+            eccentricity: 0.95,
+            ..Default::default()
+        };
+        let star = AstroBody::new_dynamic(1., Cartesian::zero(), Velocity::zero());
+        let planet = elements.to_body_around(1e-3, &star, 1.);
+        let run = |radius: Option<f64>| {
+            let mut simulation: Simulation = Simulation::new(
+                vec![star.clone(), planet.clone()],
+                Scheme::Leapfrog.build(None),
+            )
+            .with_grav(1.)
+            .with_step_time(0.1)
+            .with_regularization(radius);
+            simulation.run_for(100.);
+            simulation.drift().energy
+        };
+        assert!(run(Some(10.)) < 1e-9);
+        assert!(run(None) > 1e-3);
+    }
+}
@@ -0,0 +1,142 @@
+use crate::Args;
+use anisa_rs::config::ConfigError;
+use anisa_rs::export::Exporter;
+use anisa_rs::units::measure::Parse;
+use anisa_rs::units::time::second::Second;
+use anisa_rs::{Config, Float, Frame, Precision, Simulation};
+use std::error::Error;
+use std::fs::File;
+use std::io::{stdout, BufWriter};
+use std::path::PathBuf;
+
+/// Options of the `headless` subcommand.
+#[derive(clap::Args, Debug)]
+#[group(skip)]
+#[command(group(clap::ArgGroup::new("length").required(true).args(["duration", "steps"])))]
+pub struct Headless {
+    /// Simulated duration to run for, e.g. `1 yr`, shortening the last step to land on it
+    #[arg(long, value_parser = Second::parse)]
+    duration: Option<Second>,
+    /// Number of physics steps to run for
+    #[arg(long)]
+    steps: Option<usize>,
+    /// Simulated time per physics step, e.g. `1 h` (defaults to the config's `step_time`, or
+    /// one day)
+    #[arg(long, value_parser = Second::parse)]
+    step_time: Option<Second>,
+    /// Writes the trajectory as CSV to this file
+    #[arg(long)]
+    csv: Option<PathBuf>,
+    /// Writes the trajectory as JSON Lines to this file
+    #[arg(long)]
+    jsonl: Option<PathBuf>,
+    /// Writes energy, momentum and angular momentum and their drift as CSV to this file
+    #[arg(long)]
+    diagnostics: Option<PathBuf>,
+    /// Writes every collision as CSV to this file
+    #[arg(long)]
+    collisions: Option<PathBuf>,
+}
+
+impl Headless {
+    /// Number of whole steps of `step_time` to run, and the length of a last, shorter step that
+    /// lands on the requested duration (0 if there is none).
+    fn steps(&self, step_time: f64) -> (usize, f64) {
+        match (self.steps, self.duration) {
+            (Some(steps), _) => (steps, 0.),
+            (None, Some(duration)) => {
+                let duration: f64 = 1. * duration;
+                let steps = (duration / step_time).floor();
+                (steps as usize, duration - steps * step_time)
+            }
+            (None, None) => (0, 0.),
+        }
+    }
+
+    /// Builds the exporter, writing CSV to stdout when no output file is given.
+    fn exporter(&self, frame: Frame) -> Result<Exporter, Box<dyn Error>> {
+        let mut exporter = Exporter::new().with_frame(frame);
+        if let Some(path) = &self.csv {
+            exporter = exporter.with_csv(Box::new(BufWriter::new(File::create(path)?)));
+        }
+        if let Some(path) = &self.jsonl {
+            exporter = exporter.with_jsonl(Box::new(BufWriter::new(File::create(path)?)));
+        }
+        if let Some(path) = &self.diagnostics {
+            exporter = exporter.with_diagnostics(Box::new(BufWriter::new(File::create(path)?)));
+        }
+        if let Some(path) = &self.collisions {
+            exporter = exporter.with_collisions(Box::new(BufWriter::new(File::create(path)?)));
+        }
+        if self.csv.is_none() && self.jsonl.is_none() {
+            exporter = exporter.with_csv(Box::new(stdout().lock()));
+        }
+        Ok(exporter)
+    }
+}
+
+/// Runs the simulation described by `args` without opening a window.
+pub fn run(args: &Args, headless: &Headless) -> Result<(), Box<dyn Error>> {
+    let config = args.config()?;
+    match (config.precision, config.dimensions) {
+        (Precision::F32, 2) => simulate::<f32, 2>(&config, args, headless),
+        (Precision::F32, 3) => simulate::<f32, 3>(&config, args, headless),
+        (Precision::F64, 2) => simulate::<f64, 2>(&config, args, headless),
+        (Precision::F64, 3) => simulate::<f64, 3>(&config, args, headless),
+        (_, dimensions) => Err(ConfigError::Dimensions(dimensions).into()),
+    }
+}
+
+fn simulate<F: Float, const D: usize>(
+    config: &Config,
+    args: &Args,
+    headless: &Headless,
+) -> Result<(), Box<dyn Error>> {
+    let mut simulation = Simulation::<F, D>::from_config(config)?;
+    if let Some(step_time) = headless.step_time {
+        simulation = simulation.with_step_time(F::cast_from(1. * step_time));
+    }
+    let frame = args.frame.unwrap_or_default();
+    frame.validate(simulation.bodies().len())?;
+    let mut exporter = headless.exporter(frame)?;
+
+    record(&mut exporter, &simulation)?;
+    let step_time = simulation.step_time();
+    let (steps, remainder) = headless.steps(step_time.as_f64());
+    let last = (remainder > 0.).then_some(F::cast_from(remainder));
+    for step_time in std::iter::repeat_n(step_time, steps).chain(last) {
+        let seen = simulation.collisions().len();
+        simulation.advance(step_time);
+        exporter.record_collisions(&simulation.collisions()[seen..])?;
+        record(&mut exporter, &simulation)?;
+    }
+    exporter.flush()?;
+
+    let drift = simulation.drift();
+    eprintln!(
+        "Relative drift after {} s: energy {:.3e}, momentum {:.3e}, angular momentum {:.3e}",
+        simulation.time().as_f64(),
+        drift.energy,
+        drift.momentum,
+        drift.angular_momentum
+    );
+    if !simulation.collisions().is_empty() {
+        eprintln!("{} collisions", simulation.collisions().len());
+    }
+    Ok(())
+}
+
+fn record<F: Float, const D: usize>(
+    exporter: &mut Exporter,
+    simulation: &Simulation<F, D>,
+) -> Result<(), Box<dyn Error>> {
+    exporter.record(simulation.time(), simulation.bodies())?;
+    if exporter.wants_diagnostics() {
+        exporter.record_diagnostics(
+            simulation.time(),
+            &simulation.diagnostics(),
+            &simulation.drift(),
+        )?;
+    }
+    Ok(())
+}
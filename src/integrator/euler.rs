@@ -0,0 +1,16 @@
+use super::Integrator;
+use crate::bodies::BodySet;
+use crate::gravity::Gravity;
+use crate::quantities::Float;
+
+/// Semi-implicit Euler: kick by a full step, then drift by a full step.
+#[derive(Default, Debug, Copy, Clone)]
+pub struct Euler;
+
+impl<F: Float, const D: usize> Integrator<F, D> for Euler {
+    fn step(&mut self, bodies: &mut BodySet<F, D>, gravity: &Gravity<F>, step_time: F) {
+        gravity.gravitate_all(bodies);
+        bodies.kick(step_time);
+        bodies.drift(step_time);
+    }
+}
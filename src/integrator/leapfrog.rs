@@ -0,0 +1,18 @@
+use super::Integrator;
+use crate::bodies::BodySet;
+use crate::gravity::Gravity;
+use crate::quantities::Float;
+
+/// Kick-drift-kick leapfrog (velocity Verlet).
+#[derive(Default, Debug, Copy, Clone)]
+pub struct Leapfrog;
+
+impl<F: Float, const D: usize> Integrator<F, D> for Leapfrog {
+    fn step(&mut self, bodies: &mut BodySet<F, D>, gravity: &Gravity<F>, step_time: F) {
+        gravity.gravitate_all(bodies);
+        bodies.kick(step_time / F::cast_from(2.));
+        bodies.drift(step_time);
+        gravity.gravitate_all(bodies);
+        bodies.kick(step_time / F::cast_from(2.));
+    }
+}
@@ -0,0 +1,33 @@
+use super::{advance, Increment, Integrator};
+use crate::bodies::{BodySet, Snapshot};
+use crate::gravity::Gravity;
+use crate::quantities::Float;
+use std::slice::from_ref;
+
+/// Classic fourth order Runge–Kutta.
+#[derive(Default, Debug, Clone)]
+pub struct RungeKutta4<F, const D: usize> {
+    /// State at the start of the step, and the four stages, reused across steps.
+    start: Snapshot<F, D>,
+    stages: [Increment<F, D>; 4],
+}
+
+impl<F: Float, const D: usize> Integrator<F, D> for RungeKutta4<F, D> {
+    fn step(&mut self, bodies: &mut BodySet<F, D>, gravity: &Gravity<F>, step_time: F) {
+        bodies.save(&mut self.start);
+        let [k1, k2, k3, k4] = &mut self.stages;
+        k1.evaluate(bodies, gravity, step_time);
+        advance(bodies, &self.start, &[0.5], from_ref(k1));
+        k2.evaluate(bodies, gravity, step_time);
+        advance(bodies, &self.start, &[0.5], from_ref(k2));
+        k3.evaluate(bodies, gravity, step_time);
+        advance(bodies, &self.start, &[1.], from_ref(k3));
+        k4.evaluate(bodies, gravity, step_time);
+        advance(
+            bodies,
+            &self.start,
+            &[1. / 6., 1. / 3., 1. / 3., 1. / 6.],
+            &self.stages,
+        );
+    }
+}
@@ -0,0 +1,137 @@
+use super::{advance, Increment, Integrator};
+use crate::bodies::{BodySet, Snapshot};
+use crate::gravity::Gravity;
+use crate::quantities::Float;
+use nalgebra::SVector;
+
+/// Runge–Kutta–Fehlberg stage coefficients `a_ij`.
+const A: [&[f64]; 5] = [
+    &[1. / 4.],
+    &[3. / 32., 9. / 32.],
+    &[1932. / 2197., -7200. / 2197., 7296. / 2197.],
+    &[439. / 216., -8., 3680. / 513., -845. / 4104.],
+    &[-8. / 27., 2., -3544. / 2565., 1859. / 4104., -11. / 40.],
+];
+/// Weights of the fifth order solution.
+const B5: [f64; 6] = [
+    16. / 135.,
+    0.,
+    6656. / 12825.,
+    28561. / 56430.,
+    -9. / 50.,
+    2. / 55.,
+];
+/// Difference between the fifth and fourth order weights, i.e. the local error estimate.
+const ERROR: [f64; 6] = [
+    1. / 360.,
+    0.,
+    -128. / 4275.,
+    -2197. / 75240.,
+    1. / 50.,
+    2. / 55.,
+];
+
+const SAFETY: f64 = 0.9;
+const MIN_GROWTH: f64 = 0.2;
+const MAX_GROWTH: f64 = 5.;
+/// Smallest substep, as a fraction of the requested step, before errors are accepted anyway.
+const MIN_FRACTION: f64 = 1e-4;
+
+/// Adaptive Runge–Kutta–Fehlberg 4(5).
+///
+/// Each call to [`Integrator::step`] is split into as many substeps as needed to keep the
+/// estimated local error of every body below `tolerance`, relative to its own position and
+/// velocity. The fifth order solution is propagated (local extrapolation). The last substep size
+/// is remembered across calls.
+#[derive(Debug, Clone)]
+pub struct RungeKuttaFehlberg45<F, const D: usize> {
+    pub tolerance: f64,
+    substep: Option<f64>,
+    /// State at the start of the substep, and the six stages, reused across substeps.
+    start: Snapshot<F, D>,
+    stages: [Increment<F, D>; 6],
+}
+
+impl<F: Float, const D: usize> RungeKuttaFehlberg45<F, D> {
+    pub const DEFAULT_TOLERANCE: f64 = 1e-5;
+
+    pub fn new(tolerance: f64) -> Self {
+        RungeKuttaFehlberg45 {
+            tolerance,
+            substep: None,
+            start: Snapshot::default(),
+            stages: Default::default(),
+        }
+    }
+
+    /// Advances `bodies` from the saved `start` by `step_time`, returning the error estimate in
+    /// units of `tolerance` (i.e. the substep is acceptable if it is at most 1).
+    fn attempt(&mut self, bodies: &mut BodySet<F, D>, gravity: &Gravity<F>, step_time: F) -> f64 {
+        let (start, stages) = (&self.start, &mut self.stages);
+        stages[0].evaluate(bodies, gravity, step_time);
+        for (s, a) in A.iter().enumerate() {
+            advance(bodies, start, a, &stages[..=s]);
+            stages[s + 1].evaluate(bodies, gravity, step_time);
+        }
+        advance(bodies, start, &B5, stages);
+
+        let mut error = F::zero();
+        for i in 0..bodies.len() {
+            let mut pos_error = SVector::<F, D>::zeros();
+            let mut velocity_error = SVector::<F, D>::zeros();
+            for (weight, k) in ERROR.iter().zip(stages.iter()) {
+                pos_error += k.pos[i] * F::cast_from(*weight);
+                velocity_error += k.velocity[i] * F::cast_from(*weight);
+            }
+            // Scale by the state plus its change over the step so bodies at rest near the
+            // origin are not held to an absolute error of zero.
+            let pos_scale =
+                start.pos[i].norm() + stages[0].pos[i].norm() + F::cast_from(f64::MIN_POSITIVE);
+            let velocity_scale = start.velocity[i].norm()
+                + stages[0].velocity[i].norm()
+                + F::cast_from(f64::MIN_POSITIVE);
+            error = error
+                .max(pos_error.norm() / pos_scale)
+                .max(velocity_error.norm() / velocity_scale);
+        }
+        error.as_f64() / self.tolerance
+    }
+}
+
+impl<F: Float, const D: usize> Default for RungeKuttaFehlberg45<F, D> {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_TOLERANCE)
+    }
+}
+
+impl<F: Float, const D: usize> Integrator<F, D> for RungeKuttaFehlberg45<F, D> {
+    fn step(&mut self, bodies: &mut BodySet<F, D>, gravity: &Gravity<F>, step_time: F) {
+        if step_time == F::zero() {
+            return;
+        }
+        // Substeps are sized by magnitude so that negative steps integrate backwards.
+        let direction = step_time.signum();
+        let mut remaining = step_time.abs().as_f64();
+        let mut proposed = self.substep.unwrap_or(remaining);
+        while remaining > 0. {
+            let substep = proposed.min(remaining);
+            bodies.save(&mut self.start);
+            let mut error = self.attempt(bodies, gravity, direction * F::cast_from(substep));
+            if !error.is_finite() {
+                error = f64::MAX;
+            }
+            if error <= 1. || substep <= step_time.abs().as_f64() * MIN_FRACTION {
+                remaining -= substep;
+            } else {
+                bodies.restore(&self.start);
+            }
+            let growth = if error == 0. {
+                MAX_GROWTH
+            } else {
+                (SAFETY * error.powf(-0.2)).clamp(MIN_GROWTH, MAX_GROWTH)
+            };
+            proposed = substep * growth;
+        }
+        self.substep = Some(proposed);
+    }
+}
@@ -0,0 +1,32 @@
+use super::Integrator;
+use crate::bodies::BodySet;
+use crate::gravity::Gravity;
+use crate::quantities::Float;
+
+/// Yoshida's fourth order symplectic integrator, a composition of three leapfrog steps.
+#[derive(Default, Debug, Copy, Clone)]
+pub struct Yoshida4;
+
+impl Yoshida4 {
+    /// Drift coefficients `c_1..c_4` and kick coefficients `d_1..d_3`.
+    fn coefficients() -> ([f64; 4], [f64; 3]) {
+        let w1 = 1. / (2. - 2f64.cbrt());
+        let w0 = -2f64.cbrt() * w1;
+        (
+            [w1 / 2., (w0 + w1) / 2., (w0 + w1) / 2., w1 / 2.],
+            [w1, w0, w1],
+        )
+    }
+}
+
+impl<F: Float, const D: usize> Integrator<F, D> for Yoshida4 {
+    fn step(&mut self, bodies: &mut BodySet<F, D>, gravity: &Gravity<F>, step_time: F) {
+        let (drifts, kicks) = Self::coefficients();
+        for (c, d) in drifts.iter().zip(kicks.iter()) {
+            bodies.drift(F::cast_from(*c) * step_time);
+            gravity.gravitate_all(bodies);
+            bodies.kick(F::cast_from(*d) * step_time);
+        }
+        bodies.drift(F::cast_from(drifts[3]) * step_time);
+    }
+}
@@ -0,0 +1,38 @@
+//! N-body gravity simulation engine.
+//!
+//! The [`Simulation`] owns the bodies, the physical constants and the integrator, and can be
+//! embedded by other crates. The `anisa-rs` binary is a nannou viewer (and headless exporter)
+//! built on top of it.
+
+pub mod bodies;
+pub mod clock;
+pub mod collision;
+pub mod color;
+pub mod config;
+pub mod diagnostics;
+pub mod export;
+pub mod frames;
+pub mod gravity;
+pub mod integrator;
+pub mod orbit;
+pub mod quantities;
+pub mod simulation;
+pub mod units;
+
+pub use clock::Clock;
+pub use collision::{Collision, Outcome};
+pub use config::{read_config, Config, Planet, Precision};
+pub use diagnostics::{Diagnostics, Drift};
+pub use frames::Frame;
+pub use integrator::{Integrator, Scheme};
+pub use quantities::Float;
+pub use simulation::Simulation;
+
+pub struct GravConst;
+
+impl GravConst {
+    pub const MASS_EARTH: f64 = 5.9722E24;
+    pub const DIST_AU: f64 = 149.6e6 * 1000.; //m per au
+    pub const G: f64 = 6.67428e-11; // N m2 per kg2
+    pub const TIME_STEP: f64 = units::time::SECONDS_PER_DAY; // 1 day
+}
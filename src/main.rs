@@ -1,94 +1,103 @@
-#![allow(unused_imports)]
-
-use bodies::AstroBody;
+use anisa_rs::color::Colormap;
+use anisa_rs::config::ConfigError;
+use anisa_rs::units::measure::Parse;
+use anisa_rs::units::time::second::Second;
+use anisa_rs::{read_config, Config, Float, Frame, Precision, Scheme};
+use camera::Radial;
 use clap::Parser;
-use nalgebra::SimdComplexField;
-use nannou::prelude::*;
-use quantities::dynamics::Force;
-use quantities::spatial::{Cartesian, Velocity};
-use quantities::Tensor;
-use std::error::Error;
-use std::fs;
-use std::path::{Path, PathBuf};
-use units::length::meter::Meter;
+use scene::{ColorBy, Plane};
+use std::path::PathBuf;
 
-mod bodies;
-mod quantities;
+mod camera;
+mod headless;
 mod scene;
-mod units;
-
-#[derive(Debug)]
-struct Consts {
-    grav: f32,
-    step_time: usize,
-    lock_at: usize,
-}
-
-#[derive(serde::Deserialize)]
-struct Planet {
-    pub mass: f32,
-    pub velocity: f32,
-    pub distance: f32,
-    pub color: String,
-}
-
-#[derive(serde::Deserialize)]
-struct Config {
-    pub planets: Vec<Planet>,
-}
-
-struct GravConst;
-
-// TODO Fix scaling
-
-impl GravConst {
-    const MASS_EARTH: f32 = 5.9722E24;
-    const DIST_AU: f32 = 149.6e6 * 1000.; //m per au
-    const G: f32 = 6.67428e-11; // N m2 per kg2
-    const SCALE: f32 = 250. / Self::DIST_AU; // 1AU = 100 pixel
-    const TIME_STEP: f32 = 3600. * 24.; // 1 day
-}
-
-impl Planet {
-    fn to_body_relative_to(
-        &self,
-        host: &AstroBody<Cartesian<f32, 2, Meter>, 2, 1>,
-        angle: &f32,
-    ) -> AstroBody<Cartesian<f32, 2, Meter>, 2, 1> {
-        let here = Cartesian::with_magnitude(self.distance, *angle, host.pos);
-        AstroBody::new_dynamic(
-            self.mass,
-            here,
-            Velocity::new_perpendicular_to(
-                self.velocity * 0.66e-3 * 100., // pixel per seconds
-                &here,
-                &host.pos,
-                0.,
-            ),
-        )
-        .set_color(self.color.clone())
-    }
-}
 
 #[derive(clap::Parser)]
 struct Args {
     #[arg(long, short)]
     file: String,
-    #[arg(short)]
-    grav: f32,
-    #[arg(short, long)]
+    /// Body the camera follows at first (`n`/`p` switch bodies, `b` follows the barycentre
+    /// and `o` the origin)
+    #[arg(short, long, default_value_t = 0)]
     lock: usize,
-    #[arg(short, long)]
-    speed: Option<usize>,
+    /// Reference frame to show and export positions and velocities in: inertial, barycentric,
+    /// heliocentric, body:<index> or rotating:<primary>,<secondary> (overrides `--lock`; the
+    /// export is in the simulation frame by default)
+    #[arg(long)]
+    frame: Option<Frame>,
+    /// Simulated time per real second, e.g. `30 d` (defaults to 60 physics steps per second)
+    #[arg(short, long, value_parser = Second::parse)]
+    speed: Option<Second>,
+    /// Overrides the integration scheme from the config file
+    #[arg(long, value_enum)]
+    integrator: Option<Scheme>,
+    /// How distances from the centre of the view are drawn: linear, log or power:<exponent>
+    /// (`l` switches between them)
+    #[arg(long, default_value_t = Radial::Linear)]
+    radial: Radial,
+    /// Plane to project the bodies onto, for 3D simulations
+    #[arg(long, value_enum, default_value_t = Plane::Xy)]
+    plane: Plane,
+    /// Colors bodies by this quantity instead of the color from the config file
+    #[arg(long, value_enum)]
+    color_by: Option<ColorBy>,
+    /// Colormap for `--color-by`
+    #[arg(long, value_enum, default_value_t = Colormap::Viridis)]
+    colormap: Colormap,
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+impl Args {
+    /// Reads the config file, applying any overrides given on the command line.
+    fn config(&self) -> Result<Config, anisa_rs::config::ConfigError> {
+        let mut config = read_config(PathBuf::from(&self.file))?;
+        if let Some(scheme) = self.integrator {
+            config.integrator = scheme;
+        }
+        Ok(config)
+    }
 }
 
-fn read_config(file: PathBuf) -> Result<Config, nannou::io::TomlFileLoadError> {
-    load_from_toml(file)
+#[derive(clap::Subcommand)]
+enum Command {
+    /// Runs the simulation without a window and exports the state of every body at every step
+    Headless(headless::Headless),
 }
 
 fn main() {
-    nannou::app(scene::setup)
-        .update(scene::update)
-        .simple_window(scene::view)
+    let args = Args::parse();
+    if let Some(Command::Headless(headless)) = &args.command {
+        if let Err(e) = headless::run(&args, headless) {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+        return;
+    }
+    let (precision, dimensions) = match args.config() {
+        Ok(config) => (config.precision, config.dimensions),
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+    };
+    match (precision, dimensions) {
+        (Precision::F32, 2) => show::<f32, 2>(),
+        (Precision::F32, 3) => show::<f32, 3>(),
+        (Precision::F64, 2) => show::<f64, 2>(),
+        (Precision::F64, 3) => show::<f64, 3>(),
+        (_, dimensions) => {
+            eprintln!("{}", ConfigError::Dimensions(dimensions));
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Opens the viewer on a `D`-dimensional simulation in `F` precision.
+fn show<F: Float, const D: usize>() {
+    nannou::app(scene::setup::<F, D>)
+        .event(scene::event::<F, D>)
+        .update(scene::update::<F, D>)
+        .simple_window(scene::view::<F, D>)
         .run();
 }
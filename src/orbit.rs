@@ -0,0 +1,246 @@
+use crate::bodies::AstroBody;
+use crate::quantities::spatial::{Cartesian, Velocity};
+use crate::quantities::Float;
+use crate::units::length::meter::Meter;
+use crate::units::measure::Measure;
+use nalgebra::{Rotation3, SVector, Vector3};
+
+/// Newton iterations used to solve Kepler's equation.
+const KEPLER_ITERATIONS: usize = 50;
+
+/// How a planet of the config moves around its parent: `orbit = "circular"`, an eccentricity
+/// (`orbit = 0.2`) or full Keplerian elements.
+///
+/// The first two start the planet at the planet's `distance` from its parent, at periapsis, with
+/// the vis-viva speed `v² = G (M + m) (2 / r - 1 / a)` of an orbit with that eccentricity.
+#[derive(serde::Deserialize, Debug, Copy, Clone, PartialEq)]
+#[serde(
+    untagged,
+    expecting = "expected \"circular\", an eccentricity or a table of Keplerian elements"
+)]
+pub enum Orbit {
+    Circular(Circular),
+    Eccentricity(f64),
+    Elements(Elements),
+}
+
+/// The `"circular"` of [`Orbit::Circular`].
+#[derive(serde::Deserialize, Debug, Copy, Clone, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Circular {
+    Circular,
+}
+
+impl Orbit {
+    /// Elements of the orbit, starting at periapsis at `distance` from the parent and at
+    /// `argument_of_periapsis` degrees from the x axis unless given in full.
+    pub fn elements(&self, distance: Measure<Meter>, argument_of_periapsis: f64) -> Elements {
+        let eccentricity = match *self {
+            Orbit::Circular(_) => 0.,
+            Orbit::Eccentricity(eccentricity) => eccentricity,
+            Orbit::Elements(elements) => return elements,
+        };
+        Elements {
+            // Periapsis a (1 - e) at `distance`
+            semi_major_axis: Measure::Bare(distance.value() / (1. - eccentricity)),
+            eccentricity,
+            argument_of_periapsis,
+            ..Default::default()
+        }
+    }
+}
+
+/// Keplerian elements of an elliptic orbit around a parent body.
+///
+/// Angles are in degrees and the semi-major axis either has a unit (`"5.2 au"`) or is a bare
+/// number in meters. The position along the orbit is
+/// given by either `mean_anomaly` or `true_anomaly`; without either the body starts at
+/// periapsis.
+///
+/// The orbital plane is tilted by `inclination` from the x-y plane about the line of nodes,
+/// which is at `longitude_of_ascending_node` from the x axis. In 2D only planar orbits
+/// (inclination 0 or 180°) can be represented.
+#[derive(serde::Deserialize, Default, Debug, Copy, Clone, PartialEq)]
+pub struct Elements {
+    pub semi_major_axis: Measure<Meter>,
+    #[serde(default)]
+    pub eccentricity: f64,
+    #[serde(default)]
+    pub argument_of_periapsis: f64,
+    #[serde(default)]
+    pub inclination: f64,
+    #[serde(default)]
+    pub longitude_of_ascending_node: f64,
+    pub mean_anomaly: Option<f64>,
+    pub true_anomaly: Option<f64>,
+}
+
+impl Elements {
+    /// Checks that the elements describe a single, bound orbit.
+    pub fn validate(&self) -> Result<(), String> {
+        if !(0. ..1.).contains(&self.eccentricity) {
+            return Err(format!(
+                "eccentricity must be in [0, 1), got {}",
+                self.eccentricity
+            ));
+        }
+        if self.semi_major_axis.value() <= 0. {
+            return Err(format!(
+                "semi_major_axis must be positive, got {} m",
+                self.semi_major_axis.value()
+            ));
+        }
+        if !(0. ..=180.).contains(&self.inclination) {
+            return Err(format!(
+                "inclination must be in [0, 180], got {}",
+                self.inclination
+            ));
+        }
+        if self.mean_anomaly.is_some() && self.true_anomaly.is_some() {
+            return Err("only one of mean_anomaly and true_anomaly can be given".to_string());
+        }
+        Ok(())
+    }
+
+    /// True anomaly in radians, solving Kepler's equation `M = E - e sin E` if needed.
+    pub fn true_anomaly(&self) -> f64 {
+        let e = self.eccentricity;
+        match (self.true_anomaly, self.mean_anomaly) {
+            (Some(nu), _) => nu.to_radians(),
+            (None, Some(mean)) => {
+                let mean = mean.to_radians();
+                let mut eccentric = if e < 0.8 { mean } else { std::f64::consts::PI };
+                for _ in 0..KEPLER_ITERATIONS {
+                    let delta =
+                        (eccentric - e * eccentric.sin() - mean) / (1. - e * eccentric.cos());
+                    eccentric -= delta;
+                    if delta.abs() < 1e-7 {
+                        break;
+                    }
+                }
+                2. * ((1. + e).sqrt() * (eccentric / 2.).sin())
+                    .atan2((1. - e).sqrt() * (eccentric / 2.).cos())
+            }
+            (None, None) => 0.,
+        }
+    }
+
+    /// Whether the orbit lies in the x-y plane, i.e. can be represented in 2D.
+    pub fn is_planar(&self) -> bool {
+        self.inclination == 0. || self.inclination == 180.
+    }
+
+    /// Position and velocity relative to the parent, for a gravitational parameter `mu`
+    /// (`G (M_parent + m)`).
+    ///
+    /// In fewer than 3 dimensions, the out-of-plane components are dropped. The elements are
+    /// always worked out in `f64`, whatever the precision of the result.
+    pub fn state_vector<F: Float, const D: usize>(
+        &self,
+        mu: F,
+    ) -> (
+        Cartesian<F, D, Meter>,
+        Velocity<Cartesian<F, D, Meter>, D, 1>,
+    ) {
+        let (e, mu) = (self.eccentricity, mu.as_f64());
+        let nu = self.true_anomaly();
+        let semi_latus_rectum = self.semi_major_axis.value() * (1. - e * e);
+        let radius = semi_latus_rectum / (1. + e * nu.cos());
+        let speed = (mu / semi_latus_rectum).sqrt();
+        // Perifocal frame: periapsis along x, orbit normal along z
+        let pos = Vector3::new(radius * nu.cos(), radius * nu.sin(), 0.);
+        let velocity = Vector3::new(-speed * nu.sin(), speed * (e + nu.cos()), 0.);
+        let rotate = |axis, degrees: f64| Rotation3::from_axis_angle(&axis, degrees.to_radians());
+        let rotation = rotate(Vector3::z_axis(), self.longitude_of_ascending_node)
+            * rotate(Vector3::x_axis(), self.inclination)
+            * rotate(Vector3::z_axis(), self.argument_of_periapsis);
+        let (pos, velocity) = (rotation * pos, rotation * velocity);
+        (
+            Cartesian::from_vector(SVector::from_fn(|k, _| {
+                F::cast_from(pos.get(k).copied().unwrap_or(0.))
+            })),
+            Velocity::from_vector(SVector::from_fn(|k, _| {
+                F::cast_from(velocity.get(k).copied().unwrap_or(0.))
+            })),
+        )
+    }
+
+    /// Creates a body of `mass` on this orbit around `parent`.
+    pub fn to_body_around<F: Float, const D: usize>(
+        &self,
+        mass: F,
+        parent: &AstroBody<Cartesian<F, D, Meter>, D, 1>,
+        grav: F,
+    ) -> AstroBody<Cartesian<F, D, Meter>, D, 1> {
+        let (pos, velocity) = self.state_vector(grav * (parent.mass + mass));
+        AstroBody::new_dynamic(mass, parent.pos + pos, parent.velocity + velocity)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::quantities::Tensor;
+
+    /// Ensures that periapsis and apoapsis have the radius and vis-viva speed of the ellipse.
+    #[test]
+    fn test_apsides() {
+        let (a, e, mu) = (2., 0.5, 3.);
+        let periapsis = Elements {
+            semi_major_axis: Measure::Bare(a),
+            eccentricity: e,
+            argument_of_periapsis: 90.,
+            ..Default::default()
+        };
+        let apoapsis = Elements {
+            mean_anomaly: Some(180.),
+            ..periapsis
+        };
+        for (elements, radius) in [(periapsis, a * (1. - e)), (apoapsis, a * (1. + e))] {
+            let (pos, velocity) = elements.state_vector::<f64, 2>(mu);
+            let speed = (mu * (2. / radius - 1. / a)).sqrt();
+            assert!((pos.covariant().magnitude() - radius).abs() < 1e-5);
+            assert!((velocity.covariant().magnitude() - speed).abs() < 1e-5);
+            // Velocity is perpendicular to the radius at both apsides
+            assert!(pos.covariant().dot(velocity.covariant()).abs() < 1e-5);
+        }
+    }
+
+    /// Ensures that an inclined orbit is normal to `(sin Ω sin i, -cos Ω sin i, cos i)`.
+    #[test]
+    fn test_orbital_plane() {
+        let elements = Elements {
+            semi_major_axis: Measure::Bare(1.),
+            eccentricity: 0.3,
+            argument_of_periapsis: 40.,
+            inclination: 30.,
+            longitude_of_ascending_node: 120.,
+            true_anomaly: Some(75.),
+            ..Default::default()
+        };
+        let (pos, velocity) = elements.state_vector::<f64, 3>(1.);
+        let normal = pos.covariant().cross(velocity.covariant()).normalize();
+        let (node, inclination) = (120f64.to_radians(), 30f64.to_radians());
+        let expected = SVector::<f64, 3>::new(
+            node.sin() * inclination.sin(),
+            -node.cos() * inclination.sin(),
+            inclination.cos(),
+        );
+        assert!((normal - expected).norm() < 1e-5);
+
+        // The same orbit laid flat matches the 2D one rotated by Ω + ω
+        let flat = Elements {
+            inclination: 0.,
+            ..elements
+        };
+        let (pos_3d, _) = flat.state_vector::<f64, 3>(1.);
+        let (pos_2d, _) = Elements {
+            argument_of_periapsis: 160.,
+            longitude_of_ascending_node: 0.,
+            ..flat
+        }
+        .state_vector::<f64, 2>(1.);
+        assert!((pos_3d.covariant().xy() - pos_2d.covariant()).norm() < 1e-5);
+        assert_eq!(pos_3d.covariant().z, 0.);
+    }
+}
@@ -1,17 +1,38 @@
-use nalgebra::{
-    ArrayStorage, ComplexField, Const, Dim, Dyn, Matrix, RawStorage, Scalar, SimdComplexField,
-    SimdRealField, Storage,
-};
-use std::{
-    fmt::Debug,
-    ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign},
-};
+use nalgebra::{ArrayStorage, ComplexField, Const, Matrix, RealField, Scalar};
 
 pub mod dynamics;
 pub mod spatial;
 
+/// Floating point type the physics is computed in, `f32` or `f64`.
+pub trait Float: RealField + Copy + Default + Send + Sync + serde::Serialize {
+    fn cast_from(value: f64) -> Self;
+    fn as_f64(self) -> f64;
+
+    fn as_f32(self) -> f32 {
+        self.as_f64() as f32
+    }
+}
+
+impl Float for f32 {
+    fn cast_from(value: f64) -> Self {
+        value as f32
+    }
+    fn as_f64(self) -> f64 {
+        self as f64
+    }
+}
+
+impl Float for f64 {
+    fn cast_from(value: f64) -> Self {
+        value
+    }
+    fn as_f64(self) -> f64 {
+        self
+    }
+}
+
 pub trait Tensor<const R: usize, const C: usize> {
-    type Value: Scalar + ComplexField;
+    type Value: Scalar + ComplexField + Default;
     fn covariant(
         &self,
     ) -> &Matrix<Self::Value, Const<R>, Const<C>, ArrayStorage<Self::Value, R, C>>;
@@ -21,16 +42,11 @@ pub trait Tensor<const R: usize, const C: usize> {
     // }
 
     fn values(&self) -> Vec<Self::Value> {
-        self.covariant().data.as_slice().clone().to_vec()
+        self.covariant().data.as_slice().to_vec()
     }
 
     fn normed_values(&self) -> Vec<Self::Value> {
-        self.covariant()
-            .normalize()
-            .data
-            .as_slice()
-            .clone()
-            .to_vec()
+        self.covariant().normalize().data.as_slice().to_vec()
     }
 }
 
@@ -38,13 +54,12 @@ pub trait Deriveable {
     type Manipulated;
     type Responding;
 
-    type Output: AntiDeriveable<Output=Self>;
+    type Output: AntiDeriveable<Output = Self>;
 }
 
 pub trait AntiDeriveable {
     type Manipulated;
     type Responding;
 
-    type Output: Deriveable<Output=Self>;
-
+    type Output: Deriveable<Output = Self>;
 }
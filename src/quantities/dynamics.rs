@@ -1,9 +1,8 @@
 use crate::quantities::spatial::{Acceleration, Cartesian};
-use crate::quantities::Tensor;
-use nalgebra::{ArrayStorage, ComplexField, Const, Matrix};
-use std::ops::{Add, AddAssign, Neg};
-use crate::units::Unit;
+use crate::quantities::{Float, Tensor};
 use crate::units::length::meter::Meter;
+use nalgebra::{ArrayStorage, Const, Matrix, SVector};
+use std::ops::{Add, AddAssign, Neg};
 
 #[derive(Default, PartialEq, Debug, Copy, Clone)]
 pub struct Force<X, const R: usize, const C: usize>
@@ -22,7 +21,7 @@ where
     fn covariant(
         &self,
     ) -> &Matrix<Self::Value, Const<R>, Const<C>, ArrayStorage<Self::Value, R, C>> {
-        &self.elems.covariant()
+        self.elems.covariant()
     }
 }
 
@@ -64,29 +63,34 @@ where
     }
 }
 
-impl Force<Cartesian<f32, 2, Meter>, 2, 1> {
+impl<F: Float, const R: usize> Force<Cartesian<F, R, Meter>, R, 1> {
     pub fn zero() -> Self {
         Force {
             elems: Cartesian::zero(),
         }
     }
-    pub fn new(x: f32, y: f32) -> Self {
+    pub fn from_vector(elems: SVector<F, R>) -> Self {
         Force {
-            elems: Cartesian::new(x, y),
+            elems: Cartesian::from_vector(elems),
         }
     }
 
-    pub fn horizontal(&self) -> f32 {
+    pub fn horizontal(&self) -> F {
         self.elems.horizontal()
     }
-    pub fn vertical(&self) -> f32 {
+    pub fn vertical(&self) -> F {
         self.elems.vertical()
     }
 
-    pub fn acceleration_of(&self, mass: &f32) -> Acceleration<Cartesian<f32, 2, Meter>, 2, 1> {
-        Acceleration::new(
-            self.horizontal().scale(1. / mass),
-            self.vertical().scale(1. / mass),
-        )
+    pub fn acceleration_of(&self, mass: &F) -> Acceleration<Cartesian<F, R, Meter>, R, 1> {
+        Acceleration::from_vector(self.covariant().unscale(*mass))
+    }
+}
+
+impl<F: Float> Force<Cartesian<F, 2, Meter>, 2, 1> {
+    pub fn new(x: F, y: F) -> Self {
+        Force {
+            elems: Cartesian::new(x, y),
+        }
     }
 }
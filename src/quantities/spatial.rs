@@ -1,12 +1,11 @@
-use crate::quantities::Tensor;
+use crate::quantities::{Float, Tensor};
 use crate::units::length::meter::Meter;
-use crate::units::length::pixel::Pixel;
 use crate::units::length::DistanceUnit;
 use crate::units::Unit;
-use nalgebra::{ArrayStorage, Const, Matrix, Rotation2, SVector, SimdComplexField, SimdRealField};
+use nalgebra::{ArrayStorage, Const, Matrix, Rotation2, SVector, SimdRealField};
 use std::{
     fmt::Debug,
-    ops::{Add, AddAssign, Div, Neg, Sub},
+    ops::{Add, AddAssign, Mul, Neg, Sub},
 };
 
 #[derive(PartialEq, Clone, Copy, Debug)]
@@ -26,26 +25,6 @@ impl<F: SimdRealField, const R: usize, U: DistanceUnit> Sub for Cartesian<F, R,
     }
 }
 
-impl<const R: usize> From<Cartesian<f32, R, Meter>> for Cartesian<f32, R, Pixel> {
-    fn from(value: Cartesian<f32, R, Meter>) -> Self {
-        Self {
-            elems: value.elems.map(|x| x / value.unit.meter()),
-            unit: Pixel::unit(),
-        }
-    }
-}
-
-impl<const R: usize> From<Cartesian<f32, R, Pixel>> for Cartesian<f32, R, Meter> {
-    fn from(value: Cartesian<f32, R, Pixel>) -> Self {
-        let scale = value.unit.meter();
-        Self {
-            elems: value.elems.map(|x| x * scale),
-            unit: Meter::unit(),
-        }
-        // todo!()
-    }
-}
-
 impl<F: SimdRealField, const R: usize, U: DistanceUnit> Quantity for Cartesian<F, R, U> {
     type UNIT = U;
 }
@@ -54,15 +33,12 @@ pub trait Quantity {
     type UNIT: Unit;
 }
 
-impl<U: DistanceUnit> Cartesian<f32, 2, U>
-where
-    Cartesian<f32, 2, U>: Sub<Cartesian<f32, 2, U>, Output = Cartesian<f32, 2, U>>,
-{
-    pub fn to<Other: DistanceUnit>(self) -> Cartesian<f32, 2, Other>
+impl<F: Float, U: DistanceUnit, const R: usize> Cartesian<F, R, U> {
+    pub fn to<Other: DistanceUnit>(self) -> Cartesian<F, R, Other>
     where
-        f32: Div<Other, Output = f32>,
+        f64: Mul<Other, Output = f64>,
     {
-        let unit: Other = U::to::<Other>();
+        let unit = F::cast_from(1. * U::to::<Other>());
         let values = self.elems.map(|x| x / unit);
         Cartesian {
             elems: values,
@@ -70,40 +46,56 @@ where
         }
     }
     pub fn zero() -> Self {
-        Cartesian::new(0., 0.)
+        Cartesian::from_vector(SVector::zeros())
     }
-    pub fn new(x: f32, y: f32) -> Self {
+    pub fn from_vector(elems: SVector<F, R>) -> Self {
         Self {
-            elems: SVector::<f32, 2>::new(x, y),
+            elems,
             unit: U::unit(),
         }
     }
-    pub fn from_velocity(velocity: &Velocity<Cartesian<f32, 2, U>, 2, 1>, step_time: f32) -> Self {
+    pub fn from_velocity(velocity: &Velocity<Cartesian<F, R, U>, R, 1>, step_time: F) -> Self {
         Cartesian {
             elems: velocity.elems.elems.scale(step_time),
             unit: U::unit(),
         }
     }
 
-    pub fn horizontal(&self) -> f32 {
-        self.covariant().x
+    pub fn horizontal(&self) -> F {
+        self.covariant()[0]
+    }
+
+    pub fn vertical(&self) -> F {
+        self.covariant()[1]
     }
 
-    pub fn vertical(&self) -> f32 {
-        self.covariant().y
+    /// Component along the `axis`-th coordinate, or 0 beyond the dimension of the space.
+    pub fn component(&self, axis: usize) -> F {
+        self.elems.get(axis).copied().unwrap_or(F::zero())
+    }
+}
+
+impl<F: Float, U: DistanceUnit> Cartesian<F, 2, U> {
+    pub fn new(x: F, y: F) -> Self {
+        Self {
+            elems: SVector::<F, 2>::new(x, y),
+            unit: U::unit(),
+        }
+    }
+
+    /// The same point in `D` dimensions, on the plane of the first two axes.
+    pub fn embed<const D: usize>(&self) -> Cartesian<F, D, U> {
+        Cartesian::from_vector(SVector::from_fn(|k, _| self.component(k)))
     }
 
     #[deprecated = "A Radial Coordinate system is planned"]
-    pub fn with_magnitude(magnitude: f32, angle: f32, origin: Cartesian<f32, 2, U>) -> Self {
-        origin - Cartesian::<f32, 2, U>::new(magnitude * angle.cos(), magnitude * angle.sin())
+    pub fn with_magnitude(magnitude: F, angle: F, origin: Cartesian<F, 2, U>) -> Self {
+        origin - Cartesian::<F, 2, U>::new(magnitude * angle.cos(), magnitude * angle.sin())
     }
 
-    pub fn rotate_perpendicular_to(
-        self,
-        other: &Cartesian<f32, 2, U>,
-        angle: f32,
-    ) -> SVector<f32, 2> {
-        ((other.elems - self.elems).transpose() * Rotation2::new(angle + 90.)).transpose()
+    pub fn rotate_perpendicular_to(self, other: &Cartesian<F, 2, U>, angle: F) -> SVector<F, 2> {
+        ((other.elems - self.elems).transpose() * Rotation2::new(angle + F::frac_pi_2()))
+            .transpose()
     }
 }
 
@@ -135,48 +127,61 @@ where
     }
 }
 
-impl Velocity<Cartesian<f32, 2, Meter>, 2, 1> {
+impl<F: Float, const R: usize> Velocity<Cartesian<F, R, Meter>, R, 1> {
     pub fn zero() -> Self {
         Velocity {
             elems: Cartesian::zero(),
         }
     }
 
-    pub fn horizontal(&self) -> f32 {
-        self.covariant().x
+    pub fn horizontal(&self) -> F {
+        self.covariant()[0]
     }
 
-    pub fn vertical(&self) -> f32 {
-        self.covariant().y
+    pub fn vertical(&self) -> F {
+        self.covariant()[1]
     }
-    pub fn new(x: f32, y: f32) -> Self {
+
+    pub fn from_vector(elems: SVector<F, R>) -> Self {
         Velocity {
-            elems: Cartesian::new(x, y),
+            elems: Cartesian::from_vector(elems),
         }
     }
 
-    pub fn new_perpendicular_to(
-        magnitude: f32,
-        here: &Cartesian<f32, 2, Meter>,
-        other: &Cartesian<f32, 2, Meter>,
-        angle: f32,
-    ) -> Velocity<Cartesian<f32, 2, Meter>, 2, 1> {
-        let dir = here.rotate_perpendicular_to(other, angle);
+    pub fn from_acceleration(accel: &Acceleration<Cartesian<F, R, Meter>, R, 1>, step: &F) -> Self {
         Velocity {
             elems: Cartesian {
-                elems: dir.normalize().scale(magnitude),
+                elems: accel.covariant().scale(*step),
                 unit: Meter::unit(),
             },
         }
     }
+}
 
-    pub fn from_acceleration(
-        accel: &Acceleration<Cartesian<f32, 2, Meter>, 2, 1>,
-        step: &f32,
-    ) -> Self {
+impl<F: Float> Velocity<Cartesian<F, 2, Meter>, 2, 1> {
+    pub fn new(x: F, y: F) -> Self {
+        Velocity {
+            elems: Cartesian::new(x, y),
+        }
+    }
+
+    /// The same velocity in `D` dimensions, on the plane of the first two axes.
+    pub fn embed<const D: usize>(&self) -> Velocity<Cartesian<F, D, Meter>, D, 1> {
+        Velocity {
+            elems: self.elems.embed(),
+        }
+    }
+
+    pub fn new_perpendicular_to(
+        magnitude: F,
+        here: &Cartesian<F, 2, Meter>,
+        other: &Cartesian<F, 2, Meter>,
+        angle: F,
+    ) -> Velocity<Cartesian<F, 2, Meter>, 2, 1> {
+        let dir = here.rotate_perpendicular_to(other, angle);
         Velocity {
             elems: Cartesian {
-                elems: accel.covariant().scale(*step),
+                elems: dir.normalize().scale(magnitude),
                 unit: Meter::unit(),
             },
         }
@@ -190,8 +195,16 @@ where
     elems: X,
 }
 
-impl Acceleration<Cartesian<f32, 2, Meter>, 2, 1> {
-    pub fn new(x: f32, y: f32) -> Self {
+impl<F: Float, const R: usize> Acceleration<Cartesian<F, R, Meter>, R, 1> {
+    pub fn from_vector(elems: SVector<F, R>) -> Self {
+        Acceleration {
+            elems: Cartesian::from_vector(elems),
+        }
+    }
+}
+
+impl<F: Float> Acceleration<Cartesian<F, 2, Meter>, 2, 1> {
+    pub fn new(x: F, y: F) -> Self {
         Acceleration {
             elems: Cartesian::new(x, y),
         }
@@ -199,8 +212,8 @@ impl Acceleration<Cartesian<f32, 2, Meter>, 2, 1> {
 }
 
 // --- Custom Trait Impls ---
-impl<const R: usize, U: DistanceUnit> Tensor<R, 1> for Cartesian<f32, R, U> {
-    type Value = f32;
+impl<F: Float, const R: usize, U: DistanceUnit> Tensor<R, 1> for Cartesian<F, R, U> {
+    type Value = F;
     fn covariant(
         &self,
     ) -> &Matrix<Self::Value, Const<R>, Const<1>, ArrayStorage<Self::Value, R, 1>> {
@@ -217,24 +230,35 @@ where
     fn covariant(
         &self,
     ) -> &Matrix<Self::Value, Const<R>, Const<C>, ArrayStorage<Self::Value, R, C>> {
-        &self.elems.covariant()
+        self.elems.covariant()
     }
 }
 
 // --- Trivial Trait Impls --
 
-impl<U: DistanceUnit> Default for Cartesian<f32, 2, U> {
+impl<F: Float, const R: usize, U: DistanceUnit> Default for Cartesian<F, R, U> {
     fn default() -> Self {
         Cartesian::zero()
     }
 }
 
-impl<const R: usize, U: DistanceUnit> Neg for Cartesian<f32, R, U> {
-    type Output = Cartesian<f32, R, U>;
+impl<F: Float, const R: usize, U: DistanceUnit> Neg for Cartesian<F, R, U> {
+    type Output = Cartesian<F, R, U>;
 
     fn neg(self) -> Self::Output {
         Cartesian {
-            elems: self.elems.scale(-1.),
+            elems: -self.elems,
+            unit: U::unit(),
+        }
+    }
+}
+
+impl<F: Float, const R: usize, U: DistanceUnit> Mul<F> for Cartesian<F, R, U> {
+    type Output = Cartesian<F, R, U>;
+
+    fn mul(self, rhs: F) -> Self::Output {
+        Cartesian {
+            elems: self.elems.scale(rhs),
             unit: U::unit(),
         }
     }
@@ -283,6 +307,20 @@ where
     }
 }
 
+impl<X, F, const R: usize, const C: usize> Mul<F> for Velocity<X, R, C>
+where
+    X: Tensor<R, C> + Quantity + Mul<F>,
+    <X as Mul<F>>::Output: Tensor<R, C> + Quantity,
+{
+    type Output = Velocity<<X as Mul<F>>::Output, R, C>;
+
+    fn mul(self, rhs: F) -> Self::Output {
+        Velocity {
+            elems: self.elems * rhs,
+        }
+    }
+}
+
 impl<X, const R: usize, const C: usize> AddAssign for Velocity<X, R, C>
 where
     X: Tensor<R, C> + Quantity + AddAssign,
@@ -1,99 +1,628 @@
-use crate::bodies::{AstroBody, Tracer};
-use crate::quantities::spatial::Cartesian;
-use crate::units::length::meter::Meter;
-use crate::units::length::pixel::Pixel;
-use crate::{read_config, Args, Consts, GravConst};
+use crate::camera::{Camera, Radial};
+use crate::Args;
+use anisa_rs::bodies::Tracer;
+use anisa_rs::color::{self, Colormap};
+use anisa_rs::frames::Transform;
+use anisa_rs::quantities::spatial::Cartesian;
+use anisa_rs::quantities::Tensor;
+use anisa_rs::units::length::meter::Meter;
+use anisa_rs::units::time::second::Second;
+use anisa_rs::units::time::SECONDS_PER_DAY;
+use anisa_rs::units::Unit;
+use anisa_rs::{Clock, Config, Float, Frame, Simulation};
 use clap::Parser;
-use nannou::color::{BLACK, BLUE, RED};
-use nannou::event::Update;
-use nannou::{App, Frame};
-use std::path::PathBuf;
+use nannou::color::{rgba8, BLACK, WHITE};
+use nannou::event::{Event, Key, MouseButton, MouseScrollDelta, Update, WindowEvent};
+use nannou::geom::{pt2, Point2, Rect};
+use nannou::App;
+use palette::WithAlpha;
+
+/// Plane the viewer projects positions onto.
+#[derive(clap::ValueEnum, Default, Debug, Copy, Clone, PartialEq)]
+pub enum Plane {
+    #[default]
+    Xy,
+    Xz,
+    Yz,
+}
+
+impl Plane {
+    /// Indices of the horizontal and vertical axes on screen.
+    fn axes(&self) -> (usize, usize) {
+        match self {
+            Plane::Xy => (0, 1),
+            Plane::Xz => (0, 2),
+            Plane::Yz => (1, 2),
+        }
+    }
+
+    /// Position of `pos` on the plane, dropping the axis normal to it.
+    fn project<F: Float, const D: usize>(
+        &self,
+        pos: &Cartesian<F, D, Meter>,
+    ) -> Cartesian<f64, 2, Meter> {
+        let (horizontal, vertical) = self.axes();
+        Cartesian::new(
+            pos.component(horizontal).as_f64(),
+            pos.component(vertical).as_f64(),
+        )
+    }
+}
+
+/// Quantity the viewer can color bodies by, through a [`Colormap`].
+///
+/// Colors span the range of the quantity over the bodies on screen, so they adapt as it changes.
+#[derive(clap::ValueEnum, Debug, Copy, Clone, PartialEq)]
+pub enum ColorBy {
+    /// Speed in the frame of the view.
+    Speed,
+    /// Specific orbital energy: kinetic plus potential energy per unit mass, in the simulation
+    /// frame.
+    Energy,
+    /// Distance to the parent from the config.
+    Distance,
+}
+
+impl ColorBy {
+    /// Value of the quantity for every body.
+    fn values<F: Float, const D: usize>(
+        &self,
+        simulation: &Simulation<F, D>,
+        transform: &Transform<F, D>,
+        parents: &[Option<usize>],
+    ) -> Vec<f64> {
+        let bodies = simulation.bodies();
+        match self {
+            ColorBy::Speed => bodies
+                .iter()
+                .map(|body| {
+                    transform
+                        .velocity(&body.pos(), &body.velocity())
+                        .covariant()
+                        .magnitude()
+                        .as_f64()
+                })
+                .collect(),
+            ColorBy::Energy => {
+                let grav = simulation.gravity().grav.as_f64();
+                bodies
+                    .iter()
+                    .map(|body| {
+                        let potential = bodies
+                            .iter()
+                            .filter(|other| other.is_massive() && other.index() != body.index())
+                            .map(|other| {
+                                let distance = (other.pos() - body.pos()).covariant().magnitude();
+                                -grav * other.mass().as_f64() / distance.as_f64()
+                            })
+                            .sum::<f64>();
+                        0.5 * body.velocity().covariant().magnitude_squared().as_f64() + potential
+                    })
+                    .collect()
+            }
+            ColorBy::Distance => bodies
+                .iter()
+                .zip(parents)
+                .map(|(body, parent)| match parent {
+                    Some(parent) => (body.pos() - bodies.get(*parent).pos())
+                        .covariant()
+                        .magnitude()
+                        .as_f64(),
+                    None => 0.,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Keeps `parents` pointing at the same bodies after body `index` is taken out of the
+/// simulation. Whatever orbited it now orbits its parent.
+fn remove_parent(parents: &mut Vec<Option<usize>>, index: usize) {
+    let grandparent = parents.remove(index);
+    for parent in parents.iter_mut() {
+        if *parent == Some(index) {
+            *parent = grandparent;
+        }
+        if let Some(parent) = parent.as_mut().filter(|parent| **parent > index) {
+            *parent -= 1;
+        }
+    }
+}
+
+/// The body after (`step` = 1) or before (-1) the one `frame` is centred on, wrapping around.
+fn cycle(frame: Frame, step: isize, count: usize) -> Frame {
+    let count = count.max(1) as isize;
+    match frame {
+        Frame::Body(index) => Frame::Body((index as isize + step).rem_euclid(count) as usize),
+        _ => Frame::Body(0),
+    }
+}
+
+/// Frame rotating with the first body and the one `frame` is centred on, or the second body.
+fn rotating(frame: Frame, count: usize) -> Option<Frame> {
+    let secondary = match frame {
+        Frame::Body(index) if index > 0 => index,
+        Frame::Rotating { .. } => return Some(frame),
+        _ => 1,
+    };
+    (secondary < count).then_some(Frame::Rotating {
+        primary: 0,
+        secondary,
+    })
+}
+
+fn describe(frame: Frame, labels: &[String]) -> String {
+    match frame {
+        Frame::Inertial => "origin".to_string(),
+        Frame::Barycentric => "barycentre".to_string(),
+        Frame::Body(index) => labels[index].clone(),
+        Frame::Rotating { primary, secondary } => {
+            format!("{} / {} (rotating)", labels[primary], labels[secondary])
+        }
+    }
+}
+
+/// Physics steps per real second when no `--speed` is given.
+const DEFAULT_STEPS_PER_SECOND: f64 = 60.;
+
+/// Zoom factor for one line of the mouse wheel.
+const ZOOM_PER_LINE: f64 = 1.25;
+
+/// Touchpads scroll by pixels: this many make up a line of the mouse wheel.
+const PIXELS_PER_LINE: f64 = 40.;
 
 #[derive(Debug)]
-pub struct Scene {
-    bodies: Vec<AstroBody<Cartesian<f32, 2, Meter>, 2, 1>>,
-    tracers: Vec<Tracer<Cartesian<f32, 2, Pixel>, 2, 1>>,
-    consts: Consts,
+struct Consts {
+    plane: Plane,
+    color_by: Option<ColorBy>,
+    colormap: Colormap,
+    /// Config the bodies start from, and start over from on reset.
+    config: Config,
+    /// Whether the integrator retraces its steps when time runs backwards.
+    reversible: bool,
 }
 
-pub fn setup(_app: &App) -> Scene {
-    let args = Args::parse();
+#[derive(Debug)]
+pub struct Scene<F: Float, const D: usize> {
+    simulation: Simulation<F, D>,
+    clock: Clock,
+    /// Frame the bodies are shown in. Only the view uses it: the simulation itself stays in its
+    /// inertial frame.
+    frame: Frame,
+    /// Name of every body, to show which one the camera follows.
+    labels: Vec<String>,
+    /// Color of every body from the config.
+    colors: Vec<palette::Srgba<u8>>,
+    /// Index of the body every body orbits, from the config.
+    parents: Vec<Option<usize>>,
+    /// Past positions of every body in `frame`, cleared when it changes.
+    tracers: Vec<Tracer<Cartesian<f64, 2, Meter>, 2, 1>>,
+    /// Whether trails are drawn. They are recorded either way.
+    show_trails: bool,
+    camera: Camera,
+    /// Last mouse position while dragging the view.
+    drag: Option<Point2>,
+    paused: bool,
+    /// Whether time runs backwards.
+    reversed: bool,
+    /// Simulated time run in either direction since the start, which trails age by.
+    trail_time: f64,
+    consts: Consts,
+}
 
-    let config = read_config(PathBuf::from(&args.file)).expect("Error reading file");
-    let planets = config.planets;
+impl<F: Float, const D: usize> Scene<F, D> {
+    fn clear_trails(&mut self) {
+        for trace in self.tracers.iter_mut() {
+            trace.clear();
+        }
+    }
 
-    let (first, others) = planets.split_first().unwrap().into();
-    let angles = ndarray::Array::linspace(0., 360., others.len());
-    let host: AstroBody<Cartesian<f32, 2, Meter>, 2, 1> = AstroBody::from(first);
+    /// Starts the bodies over as the config file has them, keeping the view as it is.
+    fn reset(&mut self) {
+        let config = &self.consts.config;
+        self.simulation = Simulation::from_config(config).expect("Error reading file");
+        self.tracers = config
+            .planets
+            .iter()
+            .map(|planet| Tracer::default().with_trail(planet.trail))
+            .collect();
+        self.labels = config
+            .planets
+            .iter()
+            .enumerate()
+            .map(|(index, planet)| planet.label(index))
+            .collect();
+        self.colors = self
+            .simulation
+            .bodies()
+            .color
+            .iter()
+            .map(|c| c.as_deref().and_then(color::parse).unwrap_or_default())
+            .collect();
+        self.parents = config.parents().expect("Error reading file");
+        self.trail_time = 0.;
+    }
 
-    let mut bodies = Vec::<AstroBody<Cartesian<f32, 2, Meter>, 2, 1>>::new();
-    let mut tracers = Vec::<Tracer<Cartesian<f32, 2, Pixel>, 2, 1>>::new();
-    bodies.push(host.clone());
-    tracers.push(Tracer::default());
+    /// Runs `steps` physics steps, backwards if time is reversed, and records the trails.
+    fn advance(&mut self, steps: usize) {
+        if steps == 0 {
+            return;
+        }
+        for _ in 0..steps {
+            let seen = self.simulation.collisions().len();
+            if self.reversed {
+                self.simulation.step_backward();
+            } else {
+                self.simulation.step();
+            }
+            // Collisions of a single step index the bodies as they were before it
+            let mut removed: Vec<usize> = self.simulation.collisions()[seen..]
+                .iter()
+                .filter_map(|c| c.removed)
+                .collect();
+            removed.sort_unstable();
+            for index in removed.into_iter().rev() {
+                self.tracers.remove(index);
+                self.labels.remove(index);
+                self.colors.remove(index);
+                remove_parent(&mut self.parents, index);
+                self.frame.remove(index);
+            }
+        }
+        self.trail_time += steps as f64 * self.simulation.step_time().as_f64();
+        let bodies = self.simulation.bodies();
+        let transform = self.frame.resolve(bodies);
+        for (body, trace) in bodies.iter().zip(self.tracers.iter_mut()) {
+            if body.is_massive() {
+                let pos = self.consts.plane.project(&transform.position(&body.pos()));
+                trace.push(pos, self.trail_time);
+            }
+        }
+    }
 
-    for (planet, angle) in others.iter().zip(angles.iter()) {
-        bodies.push(planet.to_body_relative_to(&host, angle));
-        tracers.push(Tracer::default())
+    /// Whether the simulation runs, and in which direction, for the status line.
+    fn status(&self) -> String {
+        let status = match (self.paused, self.reversed) {
+            (false, false) => "running",
+            (true, false) => "paused",
+            (false, true) => "running backwards",
+            (true, true) => "paused backwards",
+        };
+        if self.reversed && !self.consts.reversible {
+            format!(
+                "{status} (inexact with {:?})",
+                self.consts.config.integrator
+            )
+        } else {
+            status.to_string()
+        }
     }
-    dbg!(Scene {
-        bodies,
-        tracers,
+}
+
+pub fn setup<F: Float, const D: usize>(_app: &App) -> Scene<F, D> {
+    let args = Args::parse();
+
+    let config = args.config().expect("Error reading file");
+    let simulation = Simulation::<F, D>::from_config(&config).expect("Error reading file");
+    let warp = match args.speed {
+        Some(speed) => 1. * speed,
+        None => DEFAULT_STEPS_PER_SECOND * simulation.step_time().as_f64(),
+    };
+    let count = simulation.bodies().len();
+    let frame = match args.frame {
+        Some(frame) => {
+            frame.validate(count).expect("Invalid frame");
+            frame
+        }
+        None => Frame::Body(args.lock.min(count - 1)),
+    };
+    let mut scene = Scene {
+        simulation,
+        clock: Clock::new(warp),
+        frame,
+        labels: vec![],
+        colors: vec![],
+        parents: vec![],
+        tracers: vec![],
+        show_trails: true,
+        camera: Camera {
+            radial: args.radial,
+            ..Default::default()
+        },
+        drag: None,
+        paused: false,
+        reversed: false,
+        trail_time: 0.,
         consts: Consts {
-            grav: GravConst::G,
-            lock_at: args.lock,
-            step_time: match args.speed {
-                Some(s) => s,
-                None => 1,
-            },
+            plane: args.plane,
+            color_by: args.color_by,
+            colormap: args.colormap,
+            reversible: config.integrator.is_time_reversible(),
+            config,
         },
-    })
+    };
+    scene.reset();
+    scene
 }
 
-pub fn update(_app: &App, _model: &mut Scene, _update: Update) {
-    let bodies = _model.bodies.clone();
-    let shift = bodies[_model.consts.lock_at].get_shift_from_origin();
-    let vel = bodies[_model.consts.lock_at].get_velocity_shift_from_origin();
-    for (i, (body, trace)) in _model
-        .bodies
-        .iter_mut()
-        .zip(_model.tracers.iter_mut())
-        .enumerate()
-    {
-        body.gravitate(&bodies, &_model.consts.grav);
-        if i == _model.consts.lock_at {
-            body.shift_by(&shift, &vel);
-        } else {
-            body.shift_by(&-shift, &-vel);
-            body.update(1.);
+/// Keyboard shortcuts:
+/// - `]` doubles the time warp and `[` halves it.
+/// - `N` and `P` move the camera to the next and previous body, `B` to the barycentre and `O`
+///   to the origin.
+/// - `R` rotates the view with the first body and the followed one, e.g. to see tadpole and
+///   horseshoe orbits (use `--frame rotating:<primary>,<secondary>` for any other pair).
+/// - `T` shows or hides trails and `C` clears them.
+/// - The mouse wheel (or `=` and `-`) zooms, dragging pans, and `Home` resets the view.
+/// - `L` switches between linear, logarithmic and square root distances from the centre.
+/// - `Space` pauses and resumes, `.` runs a single step and pauses, `Tab` reverses time (exactly
+///   with the leapfrog and Yoshida integrators) and `Backspace` starts the bodies over.
+pub fn event<F: Float, const D: usize>(_app: &App, _model: &mut Scene<F, D>, event: Event) {
+    let Event::WindowEvent {
+        simple: Some(event),
+        ..
+    } = event
+    else {
+        return;
+    };
+    match event {
+        WindowEvent::KeyPressed(key) => key_pressed(_model, key),
+        WindowEvent::MouseWheel(delta, _) => {
+            let lines = match delta {
+                MouseScrollDelta::LineDelta(_, lines) => lines as f64,
+                MouseScrollDelta::PixelDelta(position) => position.y / PIXELS_PER_LINE,
+            };
+            _model
+                .camera
+                .zoom_at(ZOOM_PER_LINE.powf(lines), _app.mouse.position());
+        }
+        WindowEvent::MousePressed(MouseButton::Left) => _model.drag = Some(_app.mouse.position()),
+        WindowEvent::MouseReleased(MouseButton::Left) => _model.drag = None,
+        WindowEvent::MouseMoved(position) => {
+            if let Some(last) = _model.drag {
+                _model.camera.pan(last, position);
+                _model.drag = Some(position);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn key_pressed<F: Float, const D: usize>(_model: &mut Scene<F, D>, key: Key) {
+    let count = _model.simulation.bodies().len();
+    let frame = match key {
+        Key::RBracket => {
+            _model.clock.scale_warp(2.);
+            return;
+        }
+        Key::LBracket => {
+            _model.clock.scale_warp(0.5);
+            return;
+        }
+        Key::N => cycle(_model.frame, 1, count),
+        Key::P => cycle(_model.frame, -1, count),
+        Key::B => Frame::Barycentric,
+        Key::O => Frame::Inertial,
+        Key::R => match rotating(_model.frame, count) {
+            Some(frame) => frame,
+            None => return,
+        },
+        Key::T => {
+            _model.show_trails = !_model.show_trails;
+            return;
+        }
+        Key::C => {
+            _model.clear_trails();
+            return;
+        }
+        Key::Equals => {
+            _model.camera.zoom_at(ZOOM_PER_LINE, pt2(0., 0.));
+            return;
+        }
+        Key::Minus => {
+            _model.camera.zoom_at(1. / ZOOM_PER_LINE, pt2(0., 0.));
+            return;
+        }
+        Key::Home => {
+            _model.camera = Camera {
+                radial: _model.camera.radial,
+                ..Default::default()
+            };
+            return;
         }
-        trace.pos.push_front(Cartesian::from(body.pos.clone()));
-        // trace.pos.resize(1000, Cartesian::from(body.pos.clone()));
+        Key::L => {
+            _model.camera.radial = _model.camera.radial.next();
+            return;
+        }
+        Key::Space => {
+            _model.paused = !_model.paused;
+            return;
+        }
+        Key::Period => {
+            _model.paused = true;
+            _model.advance(1);
+            return;
+        }
+        Key::Tab => {
+            _model.reversed = !_model.reversed;
+            return;
+        }
+        Key::Back => {
+            _model.reset();
+            return;
+        }
+        _ => return,
+    };
+    if frame != _model.frame {
+        _model.frame = frame;
+        // Trails are in the frame, so the old ones no longer line up
+        _model.clear_trails();
     }
 }
 
-pub fn view(_app: &App, _model: &Scene, _frame: Frame) {
+pub fn update<F: Float, const D: usize>(_app: &App, _model: &mut Scene<F, D>, _update: Update) {
+    if _model.paused {
+        return;
+    }
+    let steps = _model.clock.tick(
+        Second::new(_update.since_last.as_secs_f64()),
+        Second::new(_model.simulation.step_time().as_f64()),
+    );
+    _model.advance(steps);
+}
+
+pub fn view<F: Float, const D: usize>(_app: &App, _model: &Scene<F, D>, _frame: nannou::Frame) {
     let draw = _app.draw();
 
     draw.background().color(BLACK);
+    if _model.camera.radial != Radial::Linear {
+        draw_rings(&draw, _app.window_rect(), &_model.camera);
+    }
 
-    for (body, trace) in _model.bodies.iter().zip(_model.tracers.iter()) {
-        let coords = Cartesian::<f32, 2, Pixel>::from(body.pos);
-        // TODO Impl color
+    let bodies = _model.simulation.bodies();
+    let transform = _model.frame.resolve(bodies);
+    let colors = body_colors(_model, &transform);
+    for ((body, trace), color) in bodies.iter().zip(_model.tracers.iter()).zip(colors) {
+        let coords = _model.camera.screen_position(
+            &_model
+                .consts
+                .plane
+                .project(&transform.position(&body.pos())),
+        );
+        let fill = rgba8(color.red, color.green, color.blue, color.alpha);
+        // Test particles can number in the thousands: a single point each, without labels
+        if !body.is_massive() {
+            draw.rect().w_h(1., 1.).xy(coords).color(fill);
+            continue;
+        }
         draw.ellipse()
-            .w_h(body.radius, body.radius)
-            .x_y(coords.horizontal(), coords.vertical())
-            .color(BLUE);
-        for t in trace.pos.iter() {
-            draw.ellipse()
-                .w_h(1., 1.)
-                .color(RED)
-                .x_y(t.horizontal(), t.vertical());
+            .w_h(body.radius(), body.radius())
+            .xy(coords)
+            .color(fill);
+        if _model.show_trails && trace.len() >= 2 {
+            // Fades out from half the body's opacity at its end
+            draw.polyline()
+                .weight(1.)
+                .points_colored(trace.faded().map(|(t, age)| {
+                    let alpha = (color.alpha as f64 * 0.5 * (1. - age)) as u8;
+                    (
+                        _model.camera.screen_position(t),
+                        rgba8(color.red, color.green, color.blue, alpha),
+                    )
+                }));
         }
         draw.text(&format!(
             "{:.3}",
-            (body.velocity.horizontal().powi(2) + body.velocity.vertical().powi(2)).sqrt()
+            transform
+                .velocity(&body.pos(), &body.velocity())
+                .covariant()
+                .magnitude()
+                .as_f64()
         ))
-        .x_y(coords.horizontal(), coords.vertical());
+        .xy(coords);
     }
+    draw_hud(&draw, _app.window_rect(), _model);
+    draw_scale_bar(&draw, _app.window_rect(), &_model.camera);
     draw.to_frame(_app, &_frame).unwrap();
 }
+
+/// Circles at round distances from the centre of the view, labelled on their right, as
+/// distances no longer scale evenly across the window.
+fn draw_rings(draw: &nannou::Draw, window: Rect, camera: &Camera) {
+    let max = window.w().max(window.h()) as f64 / 2.;
+    let color = rgba8(255, 255, 255, 60);
+    for (radius, label) in camera.rings(max) {
+        draw.ellipse()
+            .no_fill()
+            .stroke(color)
+            .stroke_weight(1.)
+            .w_h(2. * radius, 2. * radius);
+        draw.text(&label)
+            .xy(pt2(radius + 4., 6.))
+            .left_justify()
+            .color(color);
+    }
+}
+
+/// A bar of a round length and the current scale, in the bottom left corner. The bar only
+/// holds for linear distances, so only the scale at the centre shows otherwise.
+fn draw_scale_bar(draw: &nannou::Draw, window: Rect, camera: &Camera) {
+    let area = window.pad(10.);
+    let text = Rect::from_w_h(300., 20.).bottom_left_of(area.pad_bottom(8.));
+    if camera.radial != Radial::Linear {
+        draw.text(&camera.describe())
+            .xy(text.xy())
+            .wh(text.wh())
+            .left_justify()
+            .color(WHITE);
+        return;
+    }
+    let (length, label) = camera.scale_bar(150.);
+    let start = pt2(area.left(), area.bottom());
+    let end = start + pt2(length, 0.);
+    draw.line().start(start).end(end).weight(1.).color(WHITE);
+    for tick in [start, end] {
+        draw.line()
+            .start(tick)
+            .end(tick + pt2(0., 5.))
+            .weight(1.)
+            .color(WHITE);
+    }
+    draw.text(&format!("{label}  ({})", camera.describe()))
+        .xy(text.xy())
+        .wh(text.wh())
+        .left_justify()
+        .color(WHITE);
+}
+
+/// Color of every body: from the config, or from the colormap with `--color-by`.
+fn body_colors<F: Float, const D: usize>(
+    scene: &Scene<F, D>,
+    transform: &Transform<F, D>,
+) -> Vec<palette::Srgba<u8>> {
+    let Some(color_by) = scene.consts.color_by else {
+        return scene.colors.clone();
+    };
+    let values = color_by.values(&scene.simulation, transform, &scene.parents);
+    let (min, max) = values
+        .iter()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), &value| {
+            (min.min(value), max.max(value))
+        });
+    let range = if max > min { max - min } else { 1. };
+    values
+        .iter()
+        .map(|value| {
+            scene
+                .consts
+                .colormap
+                .sample((value - min) / range)
+                .with_alpha(u8::MAX)
+        })
+        .collect()
+}
+
+/// Whether and which way the simulation runs, simulated date and time, time warp, frame and
+/// conservation drift since t = 0, in the top left corner.
+fn draw_hud<F: Float, const D: usize>(draw: &nannou::Draw, window: Rect, scene: &Scene<F, D>) {
+    let simulation = &scene.simulation;
+    let drift = simulation.drift();
+    let text = format!(
+        "{}  space . tab bksp\n{}\nt = {:.0} s\nwarp {:.2} d/s  [ ]\nframe {}  n p b o r\ntrails {}  t c\nΔE/E = {:.2e}\nΔp/p = {:.2e}\nΔL/L = {:.2e}",
+        scene.status(),
+        simulation.date().format("%Y-%m-%d %H:%M"),
+        simulation.time().as_f64(),
+        scene.clock.warp / SECONDS_PER_DAY,
+        describe(scene.frame, &scene.labels),
+        if scene.show_trails { "on" } else { "off" },
+        drift.energy,
+        drift.momentum,
+        drift.angular_momentum
+    );
+    let area = Rect::from_w_h(300., 180.).top_left_of(window.pad(10.));
+    draw.text(&text)
+        .xy(area.xy())
+        .wh(area.wh())
+        .left_justify()
+        .align_text_top()
+        .color(WHITE);
+}
@@ -0,0 +1,361 @@
+use crate::bodies::{BodySet, Snapshot};
+use crate::collision::{self, Collision, Outcome};
+use crate::config::{Config, ConfigError};
+use crate::diagnostics::{Diagnostics, Drift};
+use crate::gravity::regularization::Encounter;
+use crate::gravity::{Gravity, Solver};
+use crate::integrator::Integrator;
+use crate::quantities::Float;
+use crate::GravConst;
+use chrono::{Duration, NaiveDateTime};
+use rayon::{ThreadPool, ThreadPoolBuildError, ThreadPoolBuilder};
+use std::sync::Arc;
+
+/// A self-contained N-body simulation: bodies, physical constants and an integrator, in `D`
+/// dimensions (2 by default) with `F` precision (`f64` by default).
+///
+/// ```no_run
+/// use anisa_rs::{read_config, Simulation};
+///
+/// let config = read_config("bodies.toml".into()).unwrap();
+/// let mut simulation: Simulation<f64, 3> = Simulation::from_config(&config).unwrap();
+/// simulation.run_for(365. * 24. * 3600.);
+/// println!("{:?}", simulation.bodies().get(1).pos());
+/// ```
+#[derive(Debug)]
+pub struct Simulation<F: Float = f64, const D: usize = 2> {
+    bodies: BodySet<F, D>,
+    /// Positions and velocities at the start of the current step, to detect collisions.
+    previous: Snapshot<F, D>,
+    integrator: Box<dyn Integrator<F, D>>,
+    gravity: Gravity<F>,
+    step_time: F,
+    time: F,
+    /// Date and time at t = 0.
+    epoch: NaiveDateTime,
+    /// Diagnostics at t = 0, taken just before the first step.
+    start: Option<Diagnostics<F, D>>,
+    /// Distance below which pairs of bodies are regularized, if at all.
+    regularization: Option<F>,
+    /// What happens when bodies touch, if anything.
+    outcome: Option<Outcome>,
+    /// Every collision so far, in order.
+    collisions: Vec<Collision<F, D>>,
+    /// Threads forces are evaluated on, rayon's global pool (one per core) if `None`.
+    pool: Option<Arc<ThreadPool>>,
+}
+
+impl<F: Float, const D: usize> Simulation<F, D> {
+    /// Creates a simulation using the real gravitational constant, the direct-sum solver and a
+    /// step of one day. `bodies` is e.g. a `Vec` of [`AstroBody`](crate::bodies::AstroBody).
+    pub fn new(bodies: impl Into<BodySet<F, D>>, integrator: Box<dyn Integrator<F, D>>) -> Self {
+        Simulation {
+            bodies: bodies.into(),
+            previous: Snapshot::default(),
+            integrator,
+            gravity: Gravity::new(F::cast_from(GravConst::G)),
+            step_time: F::cast_from(GravConst::TIME_STEP),
+            time: F::zero(),
+            epoch: NaiveDateTime::default(),
+            start: None,
+            regularization: None,
+            outcome: None,
+            collisions: vec![],
+            pool: None,
+        }
+    }
+
+    pub fn from_config(config: &Config) -> Result<Self, ConfigError> {
+        if config.regularization.is_some() && config.solver != Solver::Direct {
+            return Err(ConfigError::RegularizedTree);
+        }
+        Self::new(
+            config.to_bodies(F::cast_from(GravConst::G))?,
+            config.integrator.build(config.tolerance),
+        )
+        .with_step_time(F::cast_from(
+            config
+                .step_time
+                .map_or(GravConst::TIME_STEP, |step_time| step_time.value()),
+        ))
+        .with_epoch(config.epoch)
+        .with_solver(config.solver)
+        .with_softening(F::cast_from(config.softening.value()))
+        .with_regularization(
+            config
+                .regularization
+                .map(|radius| F::cast_from(radius.value())),
+        )
+        .with_collisions(config.collisions)
+        .with_threads(config.threads)
+        .map_err(ConfigError::Threads)
+    }
+
+    pub fn with_grav(mut self, grav: F) -> Self {
+        self.gravity.grav = grav;
+        self
+    }
+
+    pub fn with_solver(mut self, solver: Solver) -> Self {
+        self.gravity.solver = solver;
+        self
+    }
+
+    /// Softens gravity with a Plummer length `softening`, in meters.
+    pub fn with_softening(mut self, softening: F) -> Self {
+        self.gravity.softening = softening;
+        self
+    }
+
+    /// Integrates the relative motion of pairs of bodies closer than `radius` as Kepler orbits
+    /// in KS coordinates (see [`Encounter`]), or leaves every pair to the integrator with `None`.
+    ///
+    /// Only exact with [`Solver::Direct`]: the pull between the pair is taken out of the forces
+    /// as the direct sum computes it, which the Barnes–Hut tree may have approximated.
+    pub fn with_regularization(mut self, radius: Option<F>) -> Self {
+        self.regularization = radius;
+        self
+    }
+
+    /// Evaluates forces on `threads` threads, or one per core with `None`.
+    ///
+    /// The results are the same whatever the number of threads.
+    pub fn with_threads(mut self, threads: Option<usize>) -> Result<Self, ThreadPoolBuildError> {
+        self.pool = match threads {
+            Some(threads) => Some(Arc::new(
+                ThreadPoolBuilder::new().num_threads(threads).build()?,
+            )),
+            None => None,
+        };
+        Ok(self)
+    }
+
+    /// Detects collisions between bodies with a physical radius and resolves them with
+    /// `outcome`, or lets bodies pass through each other with `None`.
+    pub fn with_collisions(mut self, outcome: Option<Outcome>) -> Self {
+        self.outcome = outcome;
+        self
+    }
+
+    pub fn with_step_time(mut self, step_time: F) -> Self {
+        self.step_time = step_time;
+        self
+    }
+
+    /// Sets the date and time at t = 0, see [`Simulation::date`].
+    pub fn with_epoch(mut self, epoch: NaiveDateTime) -> Self {
+        self.epoch = epoch;
+        self
+    }
+
+    pub fn with_integrator(mut self, integrator: Box<dyn Integrator<F, D>>) -> Self {
+        self.integrator = integrator;
+        self
+    }
+
+    /// Advances every body by one step.
+    pub fn step(&mut self) {
+        self.advance(self.step_time);
+    }
+
+    /// Takes every body one step back in time. Time-reversible schemes (see
+    /// [`Scheme::is_time_reversible`](crate::Scheme::is_time_reversible)) retrace their forward
+    /// steps up to rounding; collisions are not undone.
+    pub fn step_backward(&mut self) {
+        self.advance(-self.step_time);
+    }
+
+    /// Advances every body by `duration`, shortening the last step to land on it exactly.
+    pub fn run_for(&mut self, duration: F) {
+        let steps = (duration / self.step_time).floor().as_f64() as usize;
+        for _ in 0..steps {
+            self.step();
+        }
+        let remainder = duration - F::cast_from(steps as f64) * self.step_time;
+        if remainder > F::zero() {
+            self.advance(remainder);
+        }
+    }
+
+    /// Advances every body by a single step of `step_time` instead of the usual one, e.g. to
+    /// land on a given time.
+    pub fn advance(&mut self, step_time: F) {
+        if self.start.is_none() {
+            self.start = Some(self.diagnostics());
+        }
+        if self.outcome.is_some() {
+            self.bodies.save(&mut self.previous);
+        }
+        let encounters = match self.regularization {
+            Some(radius) => Encounter::find(&self.bodies, radius),
+            None => vec![],
+        };
+        self.gravity.regularized = encounters.iter().map(|e| (e.first, e.second)).collect();
+        let (integrator, bodies, gravity) = (&mut self.integrator, &mut self.bodies, &self.gravity);
+        match &self.pool {
+            Some(pool) => pool.install(|| integrator.step(bodies, gravity, step_time)),
+            None => integrator.step(bodies, gravity, step_time),
+        }
+        for encounter in encounters.iter() {
+            encounter.finish(&mut self.bodies, self.gravity.grav, step_time);
+        }
+        if let Some(outcome) = self.outcome {
+            self.collisions.extend(collision::resolve(
+                &self.previous,
+                &mut self.bodies,
+                outcome,
+                self.time,
+                step_time,
+            ));
+        }
+        self.time += step_time;
+    }
+
+    pub fn bodies(&self) -> &BodySet<F, D> {
+        &self.bodies
+    }
+
+    pub fn bodies_mut(&mut self) -> &mut BodySet<F, D> {
+        &mut self.bodies
+    }
+
+    pub fn gravity(&self) -> &Gravity<F> {
+        &self.gravity
+    }
+
+    pub fn step_time(&self) -> F {
+        self.step_time
+    }
+
+    /// Simulated time since the start, in seconds.
+    pub fn time(&self) -> F {
+        self.time
+    }
+
+    /// Simulated date and time now, counting from the epoch (1970-01-01 unless set with
+    /// [`Simulation::with_epoch`]), to the millisecond.
+    pub fn date(&self) -> NaiveDateTime {
+        self.epoch + Duration::milliseconds((self.time.as_f64() * 1e3).round() as i64)
+    }
+
+    /// Every collision since the start, in the order they happened.
+    pub fn collisions(&self) -> &[Collision<F, D>] {
+        &self.collisions
+    }
+
+    /// Energy, momentum and angular momentum of the bodies now.
+    pub fn diagnostics(&self) -> Diagnostics<F, D> {
+        Diagnostics::measure(&self.bodies, &self.gravity)
+    }
+
+    /// Relative drift of the conserved quantities since t = 0.
+    pub fn drift(&self) -> Drift {
+        match &self.start {
+            Some(start) => self.diagnostics().drift_since(start),
+            None => Drift::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::bodies::AstroBody;
+    use crate::integrator::Scheme;
+    use crate::quantities::spatial::{Cartesian, Velocity};
+    use crate::quantities::Tensor;
+
+    /// Ensures that `run_for` advances by exactly the requested duration.
+    #[test]
+    fn test_run_for_lands_on_duration() {
+        let body = AstroBody::new_dynamic(1., Cartesian::zero(), Velocity::new(1., 0.));
+        let mut simulation: Simulation = Simulation::new(vec![body], Scheme::Leapfrog.build(None))
+            .with_grav(0.)
+            .with_step_time(0.3);
+        simulation.run_for(1.);
+        assert_eq!(simulation.time(), 1.);
+        assert!((simulation.bodies().get(0).pos().horizontal() - 1.).abs() < 1e-12);
+    }
+
+    /// Ensures that a config asking for regularization with the Barnes–Hut solver is rejected.
+    #[test]
+    fn test_regularization_needs_direct_solver() {
+        let config: Config = toml::from_str(
+            r#"
+            regularization = "0.1 au"
+            [solver]
+            kind = "barnes-hut"
+            [[planets]]
+            mass = 1.0
+            color = "white"
+            "#,
+        )
+        .unwrap();
+        assert!(matches!(
+            Simulation::<f64, 2>::from_config(&config),
+            Err(ConfigError::RegularizedTree)
+        ));
+    }
+
+    /// Ensures that stepping back after stepping forward returns a time-reversible integrator to
+    /// where it started.
+    #[test]
+    fn test_step_backward_retraces() {
+        let (sun, earth): (f64, f64) = (1.989e30, GravConst::DIST_AU);
+        let bodies = vec![
+            AstroBody::new_dynamic(sun, Cartesian::zero(), Velocity::zero()),
+            AstroBody::new_dynamic(
+                GravConst::MASS_EARTH,
+                Cartesian::new(earth, 0.),
+                Velocity::new(0., (GravConst::G * sun / earth).sqrt()),
+            ),
+        ];
+        for scheme in [Scheme::Leapfrog, Scheme::Yoshida4] {
+            assert!(scheme.is_time_reversible());
+            let mut simulation: Simulation = Simulation::new(bodies.clone(), scheme.build(None));
+            for _ in 0..100 {
+                simulation.step();
+            }
+            for _ in 0..100 {
+                simulation.step_backward();
+            }
+            assert_eq!(simulation.time(), 0.);
+            let error = (simulation.bodies().get(1).pos() - bodies[1].pos)
+                .covariant()
+                .norm();
+            assert!(error < 1., "{scheme:?} ends {error} m away");
+        }
+    }
+
+    /// Ensures that the date follows the simulated time from the epoch.
+    #[test]
+    fn test_date_counts_from_epoch() {
+        let epoch = NaiveDateTime::parse_from_str("2024-02-28 12:00", "%Y-%m-%d %H:%M").unwrap();
+        let mut simulation: Simulation = Simulation::new(vec![], Scheme::Leapfrog.build(None))
+            .with_epoch(epoch)
+            .with_step_time(GravConst::TIME_STEP);
+        simulation.run_for(1.5 * GravConst::TIME_STEP);
+        assert_eq!(simulation.date().to_string(), "2024-03-01 00:00:00");
+    }
+
+    /// Ensures that one meter steps are resolved at 1 au in `f64`, where `f32` rounds them away.
+    #[test]
+    fn test_precision_at_astronomical_distances() {
+        fn travel<F: Float>() -> f64 {
+            let au = F::cast_from(GravConst::DIST_AU);
+            let body = AstroBody::new_dynamic(
+                F::one(),
+                Cartesian::new(au, F::zero()),
+                Velocity::new(F::one(), F::zero()),
+            );
+            let mut simulation: Simulation<F> =
+                Simulation::new(vec![body], Scheme::Leapfrog.build(None))
+                    .with_grav(F::zero())
+                    .with_step_time(F::one());
+            simulation.run_for(F::cast_from(1000.));
+            (simulation.bodies().get(0).pos().horizontal() - au).as_f64()
+        }
+        assert_eq!(travel::<f64>(), 1000.);
+        assert_eq!(travel::<f32>(), 0.);
+    }
+}
@@ -1,11 +1,12 @@
-use std::ops::{Div, Mul, MulAssign};
-
 pub mod length;
+pub mod mass;
+pub mod measure;
 pub mod time;
+pub mod velocity;
 
 pub trait Unit: Sized + Copy {
-    fn new(value: f32) -> Self;
+    fn new(value: f64) -> Self;
     fn unit() -> Self {
-        Self::new(1f32)
+        Self::new(1f64)
     }
 }
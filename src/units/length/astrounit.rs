@@ -6,5 +6,5 @@ use std::ops::{Div, Mul};
 #[derive(PartialEq, Debug, Copy, Clone, LengthQuantity)]
 #[conversion(meter = 149.6e9)]
 pub struct AstroUnit {
-    value: f32,
+    value: f64,
 }
@@ -6,5 +6,5 @@ use std::ops::{Div, Mul};
 #[derive(PartialEq, Debug, Copy, Clone, LengthQuantity)]
 #[conversion(meter = 1.)]
 pub struct Meter {
-    value: f32,
+    value: f64,
 }
@@ -1,13 +1,13 @@
 #[cfg(test)]
 mod test {
-    use length::DistanceUnit;
     use crate::units::length::astrounit::AstroUnit;
-    use crate::units::length::pixel::Pixel;
+    use crate::units::length::meter::Meter;
     use crate::units::*;
+    use length::DistanceUnit;
     #[test]
-    fn test_conversion_to_pixel() {
-        let au = AstroUnit::new(1.);
+    fn test_conversion_to_meter() {
+        let _au = AstroUnit::new(1.);
 
-        assert_eq!(AstroUnit::to::<Pixel>(), Pixel::new(100.))
+        assert_eq!(AstroUnit::to::<Meter>(), Meter::new(149.6e9))
     }
 }
@@ -0,0 +1,21 @@
+use crate::units::Unit;
+use std::ops::{Div, Mul};
+
+pub mod earthmass;
+pub mod kilogram;
+pub mod solarmass;
+
+pub trait MassUnit: Mul<f64, Output = Self> + Div<f64, Output = Self> + Unit {
+    const KILOGRAM: f64;
+
+    fn to<Other: MassUnit>() -> Other {
+        Other::new(Self::KILOGRAM / Other::KILOGRAM)
+    }
+
+    fn kilogram(self) -> kilogram::Kilogram
+    where
+        f64: Mul<Self, Output = f64>,
+    {
+        kilogram::Kilogram::new(1. * (self * Self::KILOGRAM))
+    }
+}
@@ -0,0 +1,10 @@
+use super::MassUnit;
+use crate::units::Unit;
+use quantity::MassQuantity;
+use std::ops::{Div, Mul};
+
+#[derive(PartialEq, Debug, Copy, Clone, MassQuantity)]
+#[conversion(kilogram = 5.9722e24)]
+pub struct EarthMass {
+    value: f64,
+}
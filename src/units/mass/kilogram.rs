@@ -0,0 +1,10 @@
+use super::MassUnit;
+use crate::units::Unit;
+use quantity::MassQuantity;
+use std::ops::{Div, Mul};
+
+#[derive(PartialEq, Debug, Copy, Clone, MassQuantity)]
+#[conversion(kilogram = 1.)]
+pub struct Kilogram {
+    value: f64,
+}
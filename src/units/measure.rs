@@ -0,0 +1,274 @@
+use crate::units::length::astrounit::AstroUnit;
+use crate::units::length::meter::Meter;
+use crate::units::length::DistanceUnit;
+use crate::units::mass::earthmass::EarthMass;
+use crate::units::mass::kilogram::Kilogram;
+use crate::units::mass::solarmass::SolarMass;
+use crate::units::mass::MassUnit;
+use crate::units::time::second::Second;
+use crate::units::time::{TemporalUnit, SECONDS_PER_DAY};
+use crate::units::velocity::kps::KilometerPerSecond;
+use crate::units::velocity::mps::MeterPerSecond;
+use crate::units::velocity::VelocityUnit;
+use crate::units::Unit;
+use serde::de::{Deserializer, Visitor};
+use serde::Deserialize;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::marker::PhantomData;
+use std::ops::Mul;
+
+const LENGTH_SYMBOLS: &[(&str, f64)] = &[
+    ("m", Meter::METER),
+    ("km", 1_000. * Meter::METER),
+    ("au", AstroUnit::METER),
+    ("AU", AstroUnit::METER),
+];
+const MASS_SYMBOLS: &[(&str, f64)] = &[
+    ("kg", Kilogram::KILOGRAM),
+    ("M_earth", EarthMass::KILOGRAM),
+    ("M_sun", SolarMass::KILOGRAM),
+];
+const TIME_SYMBOLS: &[(&str, f64)] = &[
+    ("s", Second::SECOND),
+    ("min", 60. * Second::SECOND),
+    ("h", 3_600. * Second::SECOND),
+    ("d", SECONDS_PER_DAY * Second::SECOND),
+    ("yr", 365.25 * SECONDS_PER_DAY * Second::SECOND),
+];
+const VELOCITY_SYMBOLS: &[(&str, f64)] = &[
+    ("m/s", MeterPerSecond::METER_PER_SECOND),
+    ("km/s", KilometerPerSecond::METER_PER_SECOND),
+    ("au/d", AstroUnit::METER / SECONDS_PER_DAY),
+];
+
+/// Physical dimension of a quantity, each with the unit symbols it can be written in.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Dimension {
+    Length,
+    Mass,
+    Time,
+    Velocity,
+}
+
+impl Dimension {
+    const ALL: [Dimension; 4] = [
+        Dimension::Length,
+        Dimension::Mass,
+        Dimension::Time,
+        Dimension::Velocity,
+    ];
+
+    /// Accepted unit symbols, with their size in the SI base unit of the dimension.
+    pub fn symbols(&self) -> &'static [(&'static str, f64)] {
+        match self {
+            Dimension::Length => LENGTH_SYMBOLS,
+            Dimension::Mass => MASS_SYMBOLS,
+            Dimension::Time => TIME_SYMBOLS,
+            Dimension::Velocity => VELOCITY_SYMBOLS,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Dimension::Length => "length",
+            Dimension::Mass => "mass",
+            Dimension::Time => "time",
+            Dimension::Velocity => "velocity",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum UnitError {
+    Malformed(String),
+    Unknown {
+        symbol: String,
+        expected: Dimension,
+    },
+    WrongDimension {
+        symbol: String,
+        expected: Dimension,
+        found: Dimension,
+    },
+}
+
+impl Display for UnitError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UnitError::Malformed(text) => write!(
+                f,
+                "`{text}` is not a quantity, expected a number and a unit such as `1.5 au`"
+            ),
+            UnitError::Unknown { symbol, expected } => write!(
+                f,
+                "unknown {} unit `{symbol}`, expected one of {}",
+                expected.name(),
+                expected
+                    .symbols()
+                    .iter()
+                    .map(|(s, _)| format!("`{s}`"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            UnitError::WrongDimension {
+                symbol,
+                expected,
+                found,
+            } => write!(
+                f,
+                "`{symbol}` is a unit of {}, expected a {}",
+                found.name(),
+                expected.name()
+            ),
+        }
+    }
+}
+
+impl Error for UnitError {}
+
+/// A unit that config values can be written in, e.g. `"1.5 au"` for [`Meter`].
+pub trait Parse: Unit {
+    const DIMENSION: Dimension;
+    /// Size of this unit in the SI base unit of its dimension.
+    const BASE: f64;
+
+    fn parse(text: &str) -> Result<Self, UnitError> {
+        let malformed = || UnitError::Malformed(text.to_string());
+        let (value, symbol) = text.trim().split_once(' ').ok_or_else(malformed)?;
+        let value: f64 = value.parse().map_err(|_| malformed())?;
+        let symbol = symbol.trim();
+        match Self::DIMENSION.symbols().iter().find(|(s, _)| *s == symbol) {
+            Some((_, size)) => Ok(Self::new(value * size / Self::BASE)),
+            None => Err(
+                match Dimension::ALL
+                    .iter()
+                    .find(|d| d.symbols().iter().any(|(s, _)| *s == symbol))
+                {
+                    Some(found) => UnitError::WrongDimension {
+                        symbol: symbol.to_string(),
+                        expected: Self::DIMENSION,
+                        found: *found,
+                    },
+                    None => UnitError::Unknown {
+                        symbol: symbol.to_string(),
+                        expected: Self::DIMENSION,
+                    },
+                },
+            ),
+        }
+    }
+}
+
+impl Parse for Meter {
+    const DIMENSION: Dimension = Dimension::Length;
+    const BASE: f64 = Meter::METER;
+}
+
+impl Parse for Kilogram {
+    const DIMENSION: Dimension = Dimension::Mass;
+    const BASE: f64 = Kilogram::KILOGRAM;
+}
+
+impl Parse for Second {
+    const DIMENSION: Dimension = Dimension::Time;
+    const BASE: f64 = Second::SECOND;
+}
+
+impl Parse for MeterPerSecond {
+    const DIMENSION: Dimension = Dimension::Velocity;
+    const BASE: f64 = MeterPerSecond::METER_PER_SECOND;
+}
+
+/// A config value that is either a bare number, in the SI unit of its dimension (kg, m, s or
+/// m/s), or a string with a unit such as `"29.78 km/s"`, converted to `U`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Measure<U> {
+    Bare(f64),
+    Unit(U),
+}
+
+impl<U> Measure<U>
+where
+    f64: Mul<U, Output = f64>,
+    U: Copy,
+{
+    /// Value in `U`, or the bare number as given.
+    pub fn value(&self) -> f64 {
+        match self {
+            Measure::Bare(value) => *value,
+            Measure::Unit(unit) => 1. * *unit,
+        }
+    }
+}
+
+impl<U> Default for Measure<U> {
+    fn default() -> Self {
+        Measure::Bare(0.)
+    }
+}
+
+impl<'de, U: Parse> Deserialize<'de> for Measure<U> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct MeasureVisitor<U>(PhantomData<U>);
+
+        impl<'de, U: Parse> Visitor<'de> for MeasureVisitor<U> {
+            type Value = Measure<U>;
+
+            fn expecting(&self, f: &mut Formatter) -> std::fmt::Result {
+                write!(
+                    f,
+                    "a number or a {} with a unit, such as \"1 {}\"",
+                    U::DIMENSION.name(),
+                    U::DIMENSION.symbols()[0].0
+                )
+            }
+
+            fn visit_f64<E: serde::de::Error>(self, v: f64) -> Result<Self::Value, E> {
+                Ok(Measure::Bare(v))
+            }
+
+            fn visit_i64<E: serde::de::Error>(self, v: i64) -> Result<Self::Value, E> {
+                Ok(Measure::Bare(v as f64))
+            }
+
+            fn visit_u64<E: serde::de::Error>(self, v: u64) -> Result<Self::Value, E> {
+                Ok(Measure::Bare(v as f64))
+            }
+
+            fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                U::parse(v).map(Measure::Unit).map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_any(MeasureVisitor(PhantomData))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Ensures that quantities convert to the target unit and that bad units are reported.
+    #[test]
+    fn test_parse() {
+        assert_eq!(Meter::parse("1.5 au"), Ok(Meter::new(1.5 * 149.6e9)));
+        assert_eq!(
+            MeterPerSecond::parse("29.78 km/s"),
+            Ok(MeterPerSecond::new(29_780.))
+        );
+        assert_eq!(Kilogram::parse("1 M_earth"), Ok(Kilogram::new(5.9722e24)));
+        assert!(matches!(
+            Meter::parse("1 parsec"),
+            Err(UnitError::Unknown { .. })
+        ));
+        assert!(matches!(
+            Meter::parse("3 km/s"),
+            Err(UnitError::WrongDimension {
+                found: Dimension::Velocity,
+                ..
+            })
+        ));
+        assert!(matches!(Meter::parse("au"), Err(UnitError::Malformed(_))));
+    }
+}
@@ -6,5 +6,5 @@ use std::ops::{Div, Mul};
 #[derive(PartialEq, Debug, Copy, Clone, TimeQuantity)]
 #[conversion(second = 1.)]
 pub struct Second {
-    value: f32,
+    value: f64,
 }
@@ -1,31 +1,20 @@
 use crate::units::Unit;
 use std::ops::{Div, Mul};
 
-pub trait VelocityUnit: Mul<f32, Output = Self> + Div<f32, Output = Self> + Unit {
-    const MPS: f32;
+pub mod kps;
+pub mod mps;
 
-    fn to<Other: TemporalUnit>() -> Other {
-        Other::new(Self::MPS / Other::MPS)
-    }
+pub trait VelocityUnit: Mul<f64, Output = Self> + Div<f64, Output = Self> + Unit {
+    const METER_PER_SECOND: f64;
 
-    fn meter_per_second(self) -> MeterPerSecond
-    where
-        f32: Mul<Self, Output = f32>,
-    {
-        MeterPerSecond::new(1. * (self * Self::MPS))
-    }
-
-    fn meter_per_hour(self) -> MeterPerSecond
-    where
-        f32: Mul<Self, Output = f32>,
-    {
-        MeterPerSecond::new(1. * ((self * Self::MPS) * SECONDS_PER_HOUR))
+    fn to<Other: VelocityUnit>() -> Other {
+        Other::new(Self::METER_PER_SECOND / Other::METER_PER_SECOND)
     }
 
-    fn meter_per_day(self) -> MeterPerSecond
+    fn meter_per_second(self) -> mps::MeterPerSecond
     where
-        f32: Mul<Self, Output = f32>,
+        f64: Mul<Self, Output = f64>,
     {
-        MeterPerSecond::new(1. * ((self * Self::MPS) * SECONDS_PER_DAY))
+        mps::MeterPerSecond::new(1. * (self * Self::METER_PER_SECOND))
     }
 }
@@ -0,0 +1,10 @@
+use super::VelocityUnit;
+use crate::units::Unit;
+use quantity::VelocityQuantity;
+use std::ops::{Div, Mul};
+
+#[derive(PartialEq, Debug, Copy, Clone, VelocityQuantity)]
+#[conversion(meter_per_second = 1000.)]
+pub struct KilometerPerSecond {
+    value: f64,
+}
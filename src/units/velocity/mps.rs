@@ -1,10 +1,10 @@
-// use super::VelocityUnit;
-// use crate::units::Unit;
-// use quantity::VelocityQuantity;
-// use std::ops::{Div, Mul};
+use super::VelocityUnit;
+use crate::units::Unit;
+use quantity::VelocityQuantity;
+use std::ops::{Div, Mul};
 
-// #[derive(PartialEq, Debug, Copy, Clone, VelocityQuantity)]
-// #[conversion(second = 1.)]
-// pub struct MeterPerSecond {
-//     value: f32,
-// }
+#[derive(PartialEq, Debug, Copy, Clone, VelocityQuantity)]
+#[conversion(meter_per_second = 1.)]
+pub struct MeterPerSecond {
+    value: f64,
+}